@@ -3,8 +3,16 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
-use simple_option::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use simple_option::state::State;
+use simple_option::msg::{
+    ApprovalsResponse, AuctionResponse, BidsResponse, CanExecuteResponse, CanSettleResponse,
+    ChildrenResponse, ConfigResponse, CostBasisResponse, CreatorClaimableResponse,
+    CreatorOfResponse, Cw20HookMsg, ExecuteMsg, ExecutePreviewResponse, ExpiredAtResponse,
+    ExportedOption, ExtensionResponse, GroupResponse, InstantiateMsg, LastExecutionResponse,
+    LimitsResponse, ListResponse, ListingsResponse, OwnerOfResponse, PartiesResponse,
+    PendingTransferResponse, PermitMsg, PermitNonceResponse, QueryMsg, ReceiveHookMsg,
+    SettingsResponse, SettleHookMsg, StatsResponse, SudoMsg, ThetaEstimateResponse,
+    TransferResponse, ValidateResponse, VersionResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,5 +23,62 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
+    export_schema(&schema_for!(SettleHookMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveHookMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema_with_title(&mut schema_for!(ConfigResponse), &out_dir, "ConfigResponse");
+    export_schema(&schema_for!(PartiesResponse), &out_dir);
+    export_schema(&schema_for!(TransferResponse), &out_dir);
+    export_schema(&schema_for!(OwnerOfResponse), &out_dir);
+    export_schema(&schema_for!(CreatorOfResponse), &out_dir);
+    export_schema_with_title(
+        &mut schema_for!(SettingsResponse),
+        &out_dir,
+        "SettingsResponse",
+    );
+    export_schema_with_title(
+        &mut schema_for!(VersionResponse),
+        &out_dir,
+        "VersionResponse",
+    );
+    export_schema(&schema_for!(LimitsResponse), &out_dir);
+    export_schema(&schema_for!(ListResponse), &out_dir);
+    export_schema(&schema_for!(ListingsResponse), &out_dir);
+    export_schema(&schema_for!(BidsResponse), &out_dir);
+    export_schema(&schema_for!(CostBasisResponse), &out_dir);
+    export_schema(&schema_for!(ExecutePreviewResponse), &out_dir);
+    export_schema(&schema_for!(PermitMsg), &out_dir);
+    export_schema(&schema_for!(PermitNonceResponse), &out_dir);
+    export_schema(&schema_for!(CanExecuteResponse), &out_dir);
+    export_schema(&schema_for!(CanSettleResponse), &out_dir);
+    export_schema(&schema_for!(ValidateResponse), &out_dir);
+    export_schema(&schema_for!(ThetaEstimateResponse), &out_dir);
+    export_schema(&schema_for!(ExpiredAtResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(ExportedOption), &out_dir);
+    export_schema(&schema_for!(ChildrenResponse), &out_dir);
+    export_schema(&schema_for!(CreatorClaimableResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema_with_title(
+        &mut schema_for!(ExtensionResponse),
+        &out_dir,
+        "ExtensionResponse",
+    );
+    export_schema_with_title(&mut schema_for!(GroupResponse), &out_dir, "GroupResponse");
+    export_schema_with_title(
+        &mut schema_for!(AuctionResponse),
+        &out_dir,
+        "AuctionResponse",
+    );
+    export_schema_with_title(
+        &mut schema_for!(PendingTransferResponse),
+        &out_dir,
+        "PendingTransferResponse",
+    );
+    export_schema_with_title(
+        &mut schema_for!(LastExecutionResponse),
+        &out_dir,
+        "LastExecutionResponse",
+    );
 }
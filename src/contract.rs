@@ -1,11 +1,46 @@
+use std::convert::TryInto;
+
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult,
+    entry_point, from_binary, to_binary, to_vec, Addr, BankMsg, Binary, Coin, ContractResult,
+    CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, ReplyOn, Response,
+    StdError, StdResult, Storage, Uint128, WasmMsg,
 };
+use cw0::Expiration;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{config, config_read, State};
+use crate::msg::{
+    Approval, ApprovalsResponse, AuctionResponse, BidInfo, BidsResponse, CanExecuteResponse,
+    CanSettleResponse, ChildrenResponse, ConfigResponse, CostBasisResponse, CreateMsg,
+    CreatorClaimableResponse, CreatorOfResponse, Cw20HookMsg, ExecuteMsg, ExecuteOptionMsg,
+    ExecutePreviewResponse, ExpiredAtResponse, ExportedOption, ExtensionResponse, GroupAction,
+    GroupResponse, InstantiateMsg, LastExecutionResponse, LimitsResponse, ListResponse, Listing,
+    ListingPrice, ListingsResponse, OptionSettledMsg, OptionStatus, OwnerOfResponse,
+    PartiesResponse, Permit, PermitMsg, PermitNonceResponse, QueryMsg, ReceiveHookMsg,
+    ReceiveOptionMsg, SettingsResponse, SettleHookMsg, SettleOutcome, StatsResponse, SudoMsg,
+    ThetaEstimateResponse, TransferResponse, ValidateResponse, VersionResponse,
+};
+use crate::state::{
+    options, AuctionBid, Bid, Bundle, DutchAuction, EnglishAuction, ExecutionRecord, Extension,
+    Group, PendingTransfer, Rounding, Settings, ShareQuorum, State, APPROVALS, AUCTIONS,
+    AUCTION_EXTENSION_BLOCKS, BIDS, BLACKLIST, BUNDLES, CHILDREN, EXECUTION_IDS, EXECUTORS,
+    EXTENSIONS, GROUPS, GROUP_APPROVALS, LAST_EXECUTION, MAX_ALLOWED_OWNERS, MAX_BUNDLE_SIZE,
+    MAX_BURN_BATCH, MAX_GROUP_MEMBERS, MAX_KEEPER_BOUNTY_BPS, MAX_MARKET_FEE_BPS, MAX_MEMO_LENGTH,
+    MAX_ROYALTY_BPS, NEXT_BUNDLE_ID, NEXT_ID, PENDING_TRANSFERS, PERMIT_NONCES, SETTINGS,
+    SINGLETON_ID,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:simple-option";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 // Note, you can use StdResult in some functions where you do not
 // make use of the custom errors
@@ -16,22 +51,64 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    if msg.expires <= _env.block.height {
-        return Err(ContractError::Std(StdError::generic_err(
-            "Cannot create expired option",
-        )));
+    if msg.keeper_bounty_bps > MAX_KEEPER_BOUNTY_BPS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "keeper_bounty_bps must not exceed {}",
+            MAX_KEEPER_BOUNTY_BPS
+        ))));
     }
 
-    let state = State {
-        creator: info.sender.clone(),
-        owner: info.sender.clone(),
-        collateral: info.funds,
+    // ensure the option is backed by collateral, unless explicitly unfunded
+    if info.funds.is_empty() && !msg.allow_unfunded {
+        return Err(ContractError::NoCollateral {});
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let admin = msg
+        .admin
+        .map(|admin| deps.api.addr_validate(&admin))
+        .transpose()?;
+    SETTINGS.save(
+        deps.storage,
+        &Settings {
+            permissionless_burn: msg.permissionless_burn,
+            grace_blocks: msg.grace_blocks,
+            keeper_bounty_bps: msg.keeper_bounty_bps,
+            max_duration_blocks: msg.max_duration_blocks,
+            admin,
+            market_fee_bps: 0,
+            treasury: None,
+            max_collateral: msg.max_collateral,
+        },
+    )?;
+    NEXT_ID.save(deps.storage, &SINGLETON_ID)?;
+
+    // the first option gets SINGLETON_ID
+    let create = CreateMsg {
         counter_offer: msg.counter_offer,
         expires: msg.expires,
+        exercise_deadline: msg.exercise_deadline,
+        rounding: msg.rounding,
+        on_settle: msg.on_settle,
+        transferable: msg.transferable,
+        transfer_lock_until: msg.transfer_lock_until,
+        allowed_owners: msg.allowed_owners,
+        lock_recipients: msg.lock_recipients,
+        royalty_bps: msg.royalty_bps,
+        premium: msg.premium,
+        owner: msg.owner,
+        auto_burn: msg.auto_burn,
     };
-    config(deps.storage).save(&state)?;
+    let expires = create.expires;
+    let unfunded = info.funds.is_empty();
+    create_option(deps, &_env, info, create, None)?;
 
-    Ok(Response::default())
+    let mut res: Response = Response::new();
+    res.add_attribute("expires_height", expires.to_string());
+    if unfunded {
+        res.add_attribute("unfunded", "true");
+    }
+    Ok(res)
 }
 
 // And declare a custom Error variant for the ones where you will want to make use of it
@@ -41,253 +118,12156 @@ pub fn execute(
     _env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // an error drops the response and its attributes, so rejected senders are logged instead
+    let api = deps.api;
+    let sender = info.sender.clone();
+    execute_msg(deps, _env, info, msg).map_err(|err| {
+        if matches!(err, ContractError::Unauthorized {}) {
+            api.debug(&format!("unauthorized_sender: {}", sender));
+        }
+        err
+    })
+}
+
+fn execute_msg(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Transfer { recipient } => try_transfer(deps, _env, info, recipient),
-        ExecuteMsg::Execute {} => try_execute(deps, _env, info),
-        ExecuteMsg::Burn {} => try_burn(deps, _env, info),
+        ExecuteMsg::Create(create) => try_create(deps, _env, info, create),
+        ExecuteMsg::Transfer {
+            id,
+            recipient,
+            execution_id,
+            memo,
+        } => try_transfer(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            recipient,
+            execution_id,
+            memo,
+        ),
+        ExecuteMsg::TransferPartial {
+            id,
+            recipient,
+            collateral,
+        } => try_transfer_partial(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            recipient,
+            collateral,
+        ),
+        ExecuteMsg::TransferWithPermit {
+            id,
+            recipient,
+            permit,
+        } => try_transfer_with_permit(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            recipient,
+            permit,
+        ),
+        ExecuteMsg::SendOption { id, contract, msg } => {
+            try_send_option(deps, _env, info, id.unwrap_or(SINGLETON_ID), contract, msg)
+        }
+        ExecuteMsg::TransferToGroup {
+            id,
+            members,
+            threshold,
+        } => try_transfer_to_group(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            members,
+            threshold,
+        ),
+        ExecuteMsg::TransferToShareholders { id, shares, quorum } => try_transfer_to_shareholders(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            shares,
+            quorum.unwrap_or_default(),
+        ),
+        ExecuteMsg::ApproveAction { id, action_hash } => {
+            try_approve_action(deps, _env, info, id.unwrap_or(SINGLETON_ID), action_hash)
+        }
+        ExecuteMsg::Approve {
+            id,
+            spender,
+            expires,
+        } => try_approve(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            spender,
+            expires.unwrap_or_default(),
+        ),
+        ExecuteMsg::Revoke { id, spender } => {
+            try_revoke(deps, _env, info, id.unwrap_or(SINGLETON_ID), spender)
+        }
+        ExecuteMsg::AddExecutor { id, addr } => {
+            try_add_executor(deps, _env, info, id.unwrap_or(SINGLETON_ID), addr)
+        }
+        ExecuteMsg::RemoveExecutor { id, addr } => {
+            try_remove_executor(deps, _env, info, id.unwrap_or(SINGLETON_ID), addr)
+        }
+        ExecuteMsg::ProposeTransfer {
+            id,
+            recipient,
+            valid_until,
+        } => try_propose_transfer(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            recipient,
+            valid_until.unwrap_or_default(),
+        ),
+        ExecuteMsg::AcceptTransfer { id } => {
+            try_accept_transfer(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::CancelTransfer { id } => {
+            try_cancel_transfer(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::CleanupProposals { limit } => try_cleanup_proposals(deps, _env, info, limit),
+        ExecuteMsg::Execute(msg) => try_execute(deps, _env, info, msg),
+        ExecuteMsg::ExecuteLenient { id } => {
+            try_execute_lenient(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::Burn { id } => try_burn(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::Poke { id } => try_poke(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::BurnMany { ids } => try_burn_many(deps, _env, info, ids),
+        ExecuteMsg::Reclaim { id } => try_reclaim(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::Relinquish { id } => {
+            try_relinquish(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::Renounce { id } => try_renounce(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::ProposeExtension { id, new_expires } => {
+            try_propose_extension(deps, _env, info, id.unwrap_or(SINGLETON_ID), new_expires)
+        }
+        ExecuteMsg::AcceptExtension { id } => {
+            try_accept_extension(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::RejectExtension { id } => {
+            try_reject_extension(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::AllowOwners { id, addresses } => {
+            try_allow_owners(deps, _env, info, id.unwrap_or(SINGLETON_ID), addresses)
+        }
+        ExecuteMsg::TransferCreator { id, recipient } => {
+            try_transfer_creator(deps, _env, info, id.unwrap_or(SINGLETON_ID), recipient)
+        }
+        ExecuteMsg::UpdateBlacklist { add, remove } => {
+            try_update_blacklist(deps, _env, info, add, remove)
+        }
+        ExecuteMsg::SetMarketFee {
+            market_fee_bps,
+            treasury,
+        } => try_set_market_fee(deps, _env, info, market_fee_bps, treasury),
+        ExecuteMsg::List {
+            id,
+            price,
+            private_to,
+        } => try_list(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            price,
+            private_to,
+        ),
+        ExecuteMsg::ListDutch {
+            id,
+            start_price,
+            end_price,
+            start_height,
+            end_height,
+            private_to,
+        } => try_list_dutch(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            DutchAuction {
+                start_price,
+                end_price,
+                start_height,
+                end_height,
+            },
+            private_to,
+        ),
+        ExecuteMsg::UpdateListing {
+            id,
+            price,
+            private_to,
+        } => try_update_listing(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            price,
+            private_to,
+        ),
+        ExecuteMsg::Delist { id } => try_delist(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::Buy { id } => try_buy(deps, _env, info, id.unwrap_or(SINGLETON_ID)),
+        ExecuteMsg::ListBundle { ids, price } => try_list_bundle(deps, _env, info, ids, price),
+        ExecuteMsg::BuyBundle { bundle_id } => try_buy_bundle(deps, _env, info, bundle_id),
+        ExecuteMsg::BuyPrimary { id } => {
+            try_buy_primary(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::StartAuction {
+            id,
+            reserve,
+            min_increment_bps,
+            end_height,
+            buy_now,
+            private_to,
+        } => try_start_auction(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            reserve,
+            min_increment_bps,
+            end_height,
+            buy_now,
+            private_to,
+        ),
+        ExecuteMsg::BidAuction { id } => {
+            try_bid_auction(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::SettleAuction { id } => {
+            try_settle_auction(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::ListCw20 {
+            id,
+            token,
+            amount,
+            private_to,
+        } => try_list_cw20(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            token,
+            amount,
+            private_to,
+        ),
+        ExecuteMsg::Receive(msg) => try_receive_cw20(deps, _env, info, msg),
+        ExecuteMsg::PlaceBid { id, valid_until } => try_place_bid(
+            deps,
+            _env,
+            info,
+            id.unwrap_or(SINGLETON_ID),
+            valid_until.unwrap_or_default(),
+        ),
+        ExecuteMsg::RefundExpiredBids { id, limit } => {
+            try_refund_expired_bids(deps, _env, info, id.unwrap_or(SINGLETON_ID), limit)
+        }
+        ExecuteMsg::WithdrawBid { id } => {
+            try_withdraw_bid(deps, _env, info, id.unwrap_or(SINGLETON_ID))
+        }
+        ExecuteMsg::AcceptBid { id, bidder } => {
+            try_accept_bid(deps, _env, info, id.unwrap_or(SINGLETON_ID), bidder)
+        }
+        ExecuteMsg::ClaimDust {} => try_claim_dust(deps, _env, info),
+        ExecuteMsg::Merge { id, other_id } => {
+            try_merge(deps, _env, info, id.unwrap_or(SINGLETON_ID), other_id)
+        }
+        ExecuteMsg::SetMemo { id, memo } => {
+            try_set_memo(deps, _env, info, id.unwrap_or(SINGLETON_ID), memo)
+        }
+        ExecuteMsg::SetReminder { id, blocks } => {
+            try_set_reminder(deps, _env, info, id.unwrap_or(SINGLETON_ID), blocks)
+        }
+        ExecuteMsg::SetPaused { id, paused } => {
+            try_set_paused(deps, _env, info, id.unwrap_or(SINGLETON_ID), paused)
+        }
+    }
+}
+
+pub fn try_create(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: CreateMsg,
+) -> Result<Response, ContractError> {
+    // ensure the option is backed by collateral, only instantiation may write it unfunded
+    if info.funds.is_empty() {
+        return Err(ContractError::NoCollateral {});
+    }
+    let id = create_option(deps, &_env, info, msg, None)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "create");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Like try_create, for the cw20 tokens `creator` sent as collateral
+pub fn try_create_cw20(
+    deps: DepsMut,
+    _env: Env,
+    creator: Addr,
+    msg: CreateMsg,
+    collateral: Cw20CoinVerified,
+) -> Result<Response, ContractError> {
+    let info = MessageInfo {
+        sender: creator,
+        funds: vec![],
+    };
+    let id = create_option(deps, &_env, info, msg, Some(collateral))?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "create");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Stores a new option written by the sender and returns its id. The sent funds are its
+/// collateral, unless given cw20 collateral
+fn create_option(
+    deps: DepsMut,
+    env: &Env,
+    info: MessageInfo,
+    msg: CreateMsg,
+    cw20_collateral: Option<Cw20CoinVerified>,
+) -> Result<u64, ContractError> {
+    if msg.expires <= env.block.height {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot create expired option",
+        )));
+    }
+
+    // ensure the exercise window closes no later than expiry
+    if let Some(deadline) = msg.exercise_deadline {
+        if deadline > msg.expires {
+            return Err(ContractError::Std(StdError::generic_err(
+                "exercise_deadline must not be after expires",
+            )));
+        }
+    }
+
+    // ensure the royalty is within bounds
+    if msg.royalty_bps > MAX_ROYALTY_BPS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "royalty_bps must not exceed {}",
+            MAX_ROYALTY_BPS
+        ))));
+    }
+    let premium = msg.premium.map(validate_price).transpose()?;
+
+    // ensure the transfer lock ends no later than expiry
+    if let Some(until) = msg.transfer_lock_until {
+        if until > msg.expires {
+            return Err(ContractError::Std(StdError::generic_err(
+                "transfer_lock_until must not be after expires",
+            )));
+        }
+    }
+
+    // ensure the owner allowlist is valid and bounded
+    let allowed_owners = msg
+        .allowed_owners
+        .map(|addresses| add_allowed_owners(deps.as_ref(), vec![], addresses))
+        .transpose()?;
+
+    // ensure the option does not run longer than allowed
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+    ensure_duration(&settings, msg.expires, env.block.height)?;
+
+    // ensure the collateral stays within the cap
+    if let Some(max_collateral) = &settings.max_collateral {
+        for coin in info.funds.iter() {
+            if let Some(cap) = max_collateral.iter().find(|c| c.denom == coin.denom) {
+                if coin.amount > cap.amount {
+                    return Err(ContractError::CollateralTooLarge {
+                        denom: coin.denom.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // ensure the settle hook points to a valid address
+    if let Some(hook) = &msg.on_settle {
+        deps.api.addr_validate(hook.contract.as_str())?;
+    }
+
+    // ensure an owner other than the creator is a valid recipient
+    let owner = match msg.owner {
+        Some(owner) => {
+            let owner = validate_recipient(deps.as_ref(), env, &owner)?;
+            ensure_not_blacklisted(deps.storage, &owner)?;
+            owner
+        }
+        None => info.sender.clone(),
+    };
+    let transferred = owner != info.sender;
+    // only the creator can offer the option at a premium
+    if transferred && premium.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "premium requires the creator to own the option",
+        )));
     }
+
+    let state = State {
+        creator: info.sender,
+        owner,
+        collateral: canonical_coins(info.funds)?,
+        cw20_collateral,
+        counter_offer: canonical_coins(msg.counter_offer)?,
+        expires: msg.expires,
+        created_at: env.block.height,
+        exercise_deadline: msg.exercise_deadline,
+        paused: false,
+        transferred,
+        rounding: msg.rounding.unwrap_or_default(),
+        on_settle: msg.on_settle,
+        transferable: msg.transferable.unwrap_or(true),
+        transfer_lock_until: msg.transfer_lock_until,
+        allowed_owners,
+        last_transfer_memo: None,
+        reminder_blocks: None,
+        lock_recipients: msg.lock_recipients,
+        royalty_bps: msg.royalty_bps,
+        auto_burn: msg.auto_burn,
+        premium,
+        ask: None,
+        cw20_ask: None,
+        auction: None,
+        bundle: None,
+        private_to: None,
+        memo: None,
+        shares: None,
+        share_quorum: ShareQuorum::default(),
+    };
+    ensure_allowed_owner(&state, &state.owner)?;
+    let id = NEXT_ID.load(deps.storage)?;
+    save_option(deps.storage, id, &state)?;
+    NEXT_ID.save(deps.storage, &(id + 1))?;
+    Ok(id)
 }
 
 pub fn try_transfer(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    recipient: Addr,
+    id: u64,
+    recipient: String,
+    execution_id: Option<String>,
+    memo: Option<String>,
 ) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
     // get state
-    let mut state: State = config(deps.storage).load()?;
-    // ensure msg.sender is owner
-    if info.sender != state.owner {
-        return Err(ContractError::Unauthorized {});
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
     }
-
-    // set new owner on state
-    state.owner = recipient.clone();
-    config(deps.storage).save(&state)?;
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner, an approved spender or a group with enough approvals
+    if !is_approved(deps.storage, &_env, id, &info.sender)? {
+        let action = GroupAction::Transfer {
+            recipient: recipient.clone(),
+        };
+        authorize_owner(deps.storage, id, &state, &info.sender, &action)?;
+    }
+    // ensure the recipient is a valid address other than the contract itself
+    let recipient = validate_recipient(deps.as_ref(), &_env, &recipient)?;
+    ensure_allowed_owner(&state, &recipient)?;
+    ensure_not_blacklisted(deps.storage, &recipient)?;
+    // ensure the memo is within bounds
+    validate_memo(&memo)?;
+    // ensure this is not a replay
+    record_execution_id(deps.storage, execution_id, _env.block.height)?;
 
     let mut res: Response = Response::new();
     res.add_attribute("action", "transfer");
-    res.add_attribute("owner", recipient);
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("sender", info.sender.as_str());
+    res.add_attribute("previous_owner", state.owner.as_str());
+    res.add_attribute("new_owner", recipient.as_str());
+    if let Some(memo) = &memo {
+        res.add_attribute("memo", memo);
+    }
+    res.set_data(to_binary(&TransferResponse {
+        id,
+        previous_owner: state.owner.clone(),
+        new_owner: recipient.clone(),
+    })?);
+    state.last_transfer_memo = memo;
+    set_owner(deps.storage, &mut res, id, &mut state, recipient)?;
+    Ok(res)
+}
+
+/// Rejects an empty memo or one longer than MAX_MEMO_LENGTH bytes
+fn validate_memo(memo: &Option<String>) -> Result<(), ContractError> {
+    match memo {
+        Some(memo) if memo.is_empty() || memo.len() > MAX_MEMO_LENGTH => {
+            Err(ContractError::InvalidMemo {
+                max: MAX_MEMO_LENGTH,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn try_transfer_with_permit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: String,
+    permit: Permit,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure the permit is live and signed by the owner
+    if permit.expires.is_expired(&_env.block) {
+        return Err(ContractError::ExpiredPermit {});
+    }
+    let signer = permit_signer(deps.as_ref(), &_env, id, &recipient, &permit)?;
+    if signer != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the permit is used once, in order
+    let nonce = PERMIT_NONCES
+        .may_load(deps.storage, &signer)?
+        .unwrap_or_default();
+    if permit.nonce != nonce {
+        return Err(ContractError::InvalidPermit {});
+    }
+    PERMIT_NONCES.save(deps.storage, &signer, &(nonce + 1))?;
+    // ensure the recipient is a valid address other than the contract itself
+    let recipient = validate_recipient(deps.as_ref(), &_env, &recipient)?;
+    ensure_allowed_owner(&state, &recipient)?;
+    ensure_not_blacklisted(deps.storage, &recipient)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "transfer_with_permit");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("relayer", info.sender.as_str());
+    res.add_attribute("previous_owner", state.owner.as_str());
+    res.add_attribute("new_owner", recipient.as_str());
+    set_owner(deps.storage, &mut res, id, &mut state, recipient)?;
     Ok(res)
 }
 
-pub fn try_execute(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+/// Verifies the permit's signature and returns the address of the key that made it
+fn permit_signer(
+    deps: Deps,
+    env: &Env,
+    id: u64,
+    recipient: &str,
+    permit: &Permit,
+) -> Result<Addr, ContractError> {
+    let msg = PermitMsg {
+        contract: env.contract.address.to_string(),
+        chain_id: env.block.chain_id.clone(),
+        id,
+        recipient: recipient.to_string(),
+        nonce: permit.nonce,
+        expires: permit.expires,
+    };
+    let hash = Sha256::digest(&to_vec(&msg)?);
+    let valid = deps
+        .api
+        .secp256k1_verify(&hash, &permit.signature, &permit.pubkey)
+        .map_err(|_| ContractError::InvalidPermit {})?;
+    if !valid {
+        return Err(ContractError::InvalidPermit {});
+    }
+    // the account address is ripemd160(sha256(pubkey))
+    let canonical = Ripemd160::digest(&Sha256::digest(&permit.pubkey));
+    Ok(deps.api.addr_humanize(&canonical.as_slice().into())?)
+}
+
+pub fn try_transfer_partial(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: String,
+    collateral: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
     // get state
-    let state: State = config(deps.storage).load()?;
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
     // ensure msg.sender is owner
     if info.sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure the recipient is a valid address other than the contract itself
+    let recipient = validate_recipient(deps.as_ref(), &_env, &recipient)?;
+    ensure_allowed_owner(&state, &recipient)?;
+    ensure_not_blacklisted(deps.storage, &recipient)?;
+    ensure_native_collateral(&state)?;
 
-    // ensure not expired
-    if _env.block.height >= state.expires {
-        return Err(ContractError::Std(StdError::generic_err("option expired")));
+    // ensure the same share of every collateral denom is carved out, and not all of it
+    let collateral = normalize_coins(collateral)?;
+    if collateral.len() != state.collateral.len() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "collateral must list every collateral denom",
+        )));
+    }
+    let mut share = None;
+    for carved in collateral.iter() {
+        let held = state
+            .collateral
+            .iter_mut()
+            .find(|c| c.denom == carved.denom)
+            .ok_or_else(|| StdError::generic_err("collateral must list every collateral denom"))?;
+        if carved.amount >= held.amount {
+            return Err(ContractError::PartialTooLarge {});
+        }
+        // the first denom sets the share, the others must match it
+        let (part, whole) = *share.get_or_insert((carved.amount, held.amount));
+        let same_share = carved.amount.checked_mul(whole).map_err(StdError::from)?
+            == held.amount.checked_mul(part).map_err(StdError::from)?;
+        if carved.amount.is_zero() || !same_share {
+            return Err(ContractError::Std(StdError::generic_err(
+                "collateral must be the same share of every collateral denom",
+            )));
+        }
+        held.amount = held
+            .amount
+            .checked_sub(carved.amount)
+            .map_err(StdError::from)?;
     }
+    let (part, whole) = share.ok_or(ContractError::NoCollateral {})?;
 
-    // ensure sending proper counter_offer
-    if info.funds != state.counter_offer {
-        return Err(ContractError::Std(StdError::generic_err(format!(
-            "must send exact counter_offer: {:?}",
-            state.counter_offer
-        ))));
+    // the matching share of the counter_offer moves along
+    let mut counter_offer = vec![];
+    for coin in state.counter_offer.iter_mut() {
+        let amount = apply_fraction(coin.amount, part.u128(), whole.u128(), state.rounding)?;
+        coin.amount = coin.amount.checked_sub(amount).map_err(StdError::from)?;
+        counter_offer.push(Coin {
+            denom: coin.denom.clone(),
+            amount,
+        });
     }
+    // rounding may leave either side owing nothing of a denom
+    state.counter_offer.retain(|coin| !coin.amount.is_zero());
+    counter_offer.retain(|coin| !coin.amount.is_zero());
+    // the listing was for the whole option
+    state.premium = None;
+    state.ask = None;
+    state.cw20_ask = None;
+    state.auction = None;
+    state.bundle = None;
+    state.private_to = None;
+    save_option(deps.storage, id, &state)?;
+
+    let child = State {
+        owner: recipient.clone(),
+        collateral,
+        counter_offer,
+        transferred: true,
+        last_transfer_memo: None,
+        reminder_blocks: None,
+        memo: None,
+        shares: None,
+        ..state
+    };
+    let child_id = NEXT_ID.load(deps.storage)?;
+    save_option(deps.storage, child_id, &child)?;
+    NEXT_ID.save(deps.storage, &(child_id + 1))?;
+    CHILDREN.save(deps.storage, (id.into(), child_id.into()), &Empty {})?;
 
-    // release counter_offer to creator
     let mut res: Response = Response::new();
-    res.add_message(BankMsg::Send {
-        to_address: state.creator.as_str().to_string(),
-        amount: state.counter_offer,
-    });
+    res.add_attribute("action", "transfer_partial");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("child_id", child_id.to_string());
+    res.add_attribute("new_owner", recipient);
+    res.add_attribute("collateral", coins_to_string(&child.collateral));
+    Ok(res)
+}
 
-    // release collateral to sender
-    res.add_message(BankMsg::Send {
-        to_address: state.owner.as_str().to_string(),
-        amount: state.collateral,
-    });
+pub fn try_send_option(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    contract: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner or an approved spender
+    if info.sender != state.owner && !is_approved(deps.storage, &_env, id, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the contract is a valid address other than this contract itself
+    let contract = validate_recipient(deps.as_ref(), &_env, &contract)?;
+    ensure_allowed_owner(&state, &contract)?;
+    ensure_not_blacklisted(deps.storage, &contract)?;
 
-    // delete the option
-    config(deps.storage).remove();
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "send_option");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("sender", info.sender.as_str());
+    res.add_attribute("previous_owner", state.owner.as_str());
+    res.add_attribute("new_owner", contract.as_str());
+    set_owner(deps.storage, &mut res, id, &mut state, contract.clone())?;
 
-    res.add_attribute("action", "execute");
+    // a failing receiver reverts the whole transfer
+    let hook = ReceiveHookMsg::ReceiveOption(ReceiveOptionMsg {
+        sender: info.sender.into(),
+        id,
+        msg,
+    });
+    res.add_message(WasmMsg::Execute {
+        contract_addr: contract.into(),
+        msg: to_binary(&hook)?,
+        send: vec![],
+    });
     Ok(res)
 }
 
-pub fn try_burn(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn try_transfer_to_group(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    members: Vec<String>,
+    threshold: u32,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
     // get state
-    let state: State = config(deps.storage).load()?;
-
-    // ensure not expired
-    if _env.block.height < state.expires {
-        return Err(ContractError::Std(StdError::generic_err("option expired")));
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
     }
-
-    // ensure sending proper counter_offer
-    if !info.funds.is_empty() {
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure every member is someone who could own the option alone
+    let mut group_members: Vec<Addr> = vec![];
+    for member in members {
+        let member = validate_recipient(deps.as_ref(), &_env, &member)?;
+        ensure_allowed_owner(&state, &member)?;
+        ensure_not_blacklisted(deps.storage, &member)?;
+        if !group_members.contains(&member) {
+            group_members.push(member);
+        }
+    }
+    if group_members.len() > MAX_GROUP_MEMBERS {
         return Err(ContractError::Std(StdError::generic_err(format!(
-            "don't send funds with burn: {:?}",
-            state.counter_offer
+            "members must not exceed {} addresses",
+            MAX_GROUP_MEMBERS
         ))));
     }
+    if threshold == 0 || threshold as usize > group_members.len() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "threshold must be between 1 and the number of members",
+        )));
+    }
 
-    // release counter_offer to creator
     let mut res: Response = Response::new();
-    res.add_message(BankMsg::Send {
-        to_address: state.creator.as_str().to_string(),
-        amount: state.collateral,
-    });
-
-    // delete the option
-    config(deps.storage).remove();
-
-    res.add_attribute("action", "burn");
+    res.add_attribute("action", "transfer_to_group");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("previous_owner", state.owner.as_str());
+    res.add_attribute("members", group_members.len().to_string());
+    res.add_attribute("threshold", threshold.to_string());
+    // the contract holds the option on the group's behalf
+    hold_option(deps.storage, &mut res, id, &mut state, &_env)?;
+    GROUPS.save(
+        deps.storage,
+        id.into(),
+        &Group {
+            members: group_members,
+            threshold,
+        },
+    )?;
     Ok(res)
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+pub fn try_transfer_to_shareholders(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    shares: Vec<(String, u64)>,
+    quorum: ShareQuorum,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
     }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_native_collateral(&state)?;
+    // ensure every holder is someone who could own the option alone, holding some shares
+    let mut holders: Vec<(Addr, u64)> = vec![];
+    for (holder, weight) in shares {
+        let holder = validate_recipient(deps.as_ref(), &_env, &holder)?;
+        ensure_allowed_owner(&state, &holder)?;
+        ensure_not_blacklisted(deps.storage, &holder)?;
+        if weight == 0 {
+            return Err(ContractError::Std(StdError::generic_err(
+                "shares must be positive",
+            )));
+        }
+        if holders.iter().any(|(other, _)| *other == holder) {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "duplicate shareholder {}",
+                holder
+            ))));
+        }
+        holders.push((holder, weight));
+    }
+    if holders.is_empty() || holders.len() > MAX_GROUP_MEMBERS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "shares must list between 1 and {} holders",
+            MAX_GROUP_MEMBERS
+        ))));
+    }
+    total_shares(&holders)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "transfer_to_shareholders");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("previous_owner", state.owner.as_str());
+    res.add_attribute("holders", holders.len().to_string());
+    // the contract holds the option on the holders' behalf
+    hold_option(deps.storage, &mut res, id, &mut state, &_env)?;
+    state.shares = Some(holders);
+    state.share_quorum = quorum;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
 }
 
-fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    config_read(deps.storage).load()
+fn total_shares(shares: &[(Addr, u64)]) -> StdResult<u64> {
+    shares.iter().try_fold(0u64, |total, (_, weight)| {
+        total
+            .checked_add(*weight)
+            .ok_or_else(|| StdError::generic_err("total shares overflow"))
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, Attribute, CosmosMsg};
+pub fn try_approve_action(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    action_hash: Binary,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // ensure the option exists
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is a member of the group owning it, or one of its shareholders
+    let group = GROUPS.may_load(deps.storage, id.into())?;
+    if !matches!(group, Some(group) if group.members.contains(&info.sender))
+        && !is_shareholder(&state, &info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies(&[]);
+    GROUP_APPROVALS.save(deps.storage, (id.into(), &info.sender), &action_hash)?;
 
-        let msg = InstantiateMsg {
-            counter_offer: coins(40, "ETH"),
-            expires: 100_000,
-        };
-        let _env = mock_env();
-        let info = mock_info("creator", &coins(1, "BTC"));
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "approve_action");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("member", info.sender.as_str());
+    Ok(res)
+}
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+fn is_shareholder(state: &State, addr: &Addr) -> bool {
+    state
+        .shares
+        .iter()
+        .flatten()
+        .any(|(holder, _)| holder == addr)
+}
 
-        // it worked, let's query the state
-        let res: State = query_config(deps.as_ref()).unwrap();
-        assert_eq!(100_000, res.expires);
-        assert_eq!("creator", res.owner.as_str());
-        assert_eq!("creator", res.creator.as_str());
-        assert_eq!(coins(1, "BTC"), res.collateral);
-        assert_eq!(coins(40, "ETH"), res.counter_offer);
+/// Ensures `sender` may act as the option's owner: the owner itself, a member of the
+/// group owning it once enough members approved `action`, or a shareholder once enough
+/// shares did. The group is dissolved then, as every group action hands the option on
+/// or settles it
+fn authorize_owner(
+    storage: &mut dyn Storage,
+    id: u64,
+    state: &State,
+    sender: &Addr,
+    action: &GroupAction,
+) -> Result<(), ContractError> {
+    if *sender == state.owner {
+        return Ok(());
+    }
+    if let Some(shares) = state
+        .shares
+        .as_ref()
+        .filter(|_| is_shareholder(state, sender))
+    {
+        return authorize_shareholders(storage, id, state, shares, sender, action);
     }
+    let group = match GROUPS.may_load(storage, id.into())? {
+        Some(group) if group.members.contains(sender) => group,
+        _ => return Err(ContractError::Unauthorized {}),
+    };
 
-    #[test]
+    // the caller counts as approving the action it calls
+    let hash = group_action_hash(id, state, action)?;
+    let mut approvals = 0;
+    for member in group.members.iter() {
+        let approved = GROUP_APPROVALS.may_load(storage, (id.into(), member))?;
+        if member == sender || approved.as_ref() == Some(&hash) {
+            approvals += 1;
+        }
+    }
+    if approvals < group.threshold {
+        return Err(ContractError::ThresholdNotMet {
+            approvals,
+            threshold: group.threshold,
+        });
+    }
+
+    GROUPS.remove(storage, id.into());
+    for member in group.members.iter() {
+        GROUP_APPROVALS.remove(storage, (id.into(), member));
+    }
+    Ok(())
+}
+
+/// Ensures the holders of enough shares approved `action`, the caller counting as approving
+fn authorize_shareholders(
+    storage: &mut dyn Storage,
+    id: u64,
+    state: &State,
+    shares: &[(Addr, u64)],
+    sender: &Addr,
+    action: &GroupAction,
+) -> Result<(), ContractError> {
+    let hash = group_action_hash(id, state, action)?;
+    let total = total_shares(shares)?;
+    let mut approved = 0;
+    for (holder, weight) in shares.iter() {
+        let approval = GROUP_APPROVALS.may_load(storage, (id.into(), holder))?;
+        if holder == sender || approval.as_ref() == Some(&hash) {
+            approved += weight;
+        }
+    }
+    let required = match state.share_quorum {
+        ShareQuorum::Majority => total / 2 + 1,
+        ShareQuorum::All => total,
+    };
+    if approved < required {
+        return Err(ContractError::SharesNotMet { approved, required });
+    }
+
+    for (holder, _) in shares.iter() {
+        GROUP_APPROVALS.remove(storage, (id.into(), holder));
+    }
+    Ok(())
+}
+
+/// Hash group members approve to let the group take `action`. It covers the option's
+/// current state, so approvals given before the option changed no longer count
+fn group_action_hash(id: u64, state: &State, action: &GroupAction) -> StdResult<Binary> {
+    let preimage = to_vec(&(id, state, action))?;
+    Ok(Binary::from(Sha256::digest(&preimage).as_slice()))
+}
+
+pub fn try_approve(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    spender: String,
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the approval can still be used
+    if expires.is_expired(&_env.block) {
+        return Err(ContractError::ExpiredApproval {});
+    }
+
+    let spender = deps.api.addr_validate(&spender)?;
+    APPROVALS.save(deps.storage, (id.into(), &spender), &expires)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "approve");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("spender", spender);
+    Ok(res)
+}
+
+pub fn try_revoke(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    spender: String,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let spender = deps.api.addr_validate(&spender)?;
+    APPROVALS.remove(deps.storage, (id.into(), &spender));
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "revoke");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("spender", spender);
+    Ok(res)
+}
+
+pub fn try_add_executor(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    addr: String,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let executor = deps.api.addr_validate(&addr)?;
+    EXECUTORS.save(deps.storage, (id.into(), &executor), &Empty {})?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "add_executor");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("executor", executor);
+    Ok(res)
+}
+
+pub fn try_remove_executor(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    addr: String,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let executor = deps.api.addr_validate(&addr)?;
+    EXECUTORS.remove(deps.storage, (id.into(), &executor));
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "remove_executor");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("executor", executor);
+    Ok(res)
+}
+
+/// Ensures `sender` may execute the option, paying the collateral to `recipient`: one of
+/// the executors the owner added, or anyone authorize_owner accepts. A group approves the
+/// recipient along with the execution, so no member can take the collateral alone
+fn authorize_executor(
+    storage: &mut dyn Storage,
+    id: u64,
+    state: &State,
+    sender: &Addr,
+    recipient: &Addr,
+) -> Result<(), ContractError> {
+    if EXECUTORS.may_load(storage, (id.into(), sender))?.is_some() {
+        return Ok(());
+    }
+    let action = GroupAction::Execute {
+        recipient: match state.shares {
+            Some(_) => None,
+            None => Some(recipient.to_string()),
+        },
+    };
+    authorize_owner(storage, id, state, sender, &action)
+}
+
+/// Rejects an owner outside the option's allowlist, the creator is always allowed
+fn ensure_allowed_owner(state: &State, owner: &Addr) -> Result<(), ContractError> {
+    match &state.allowed_owners {
+        Some(allowed) if *owner != state.creator && !allowed.contains(owner) => {
+            Err(ContractError::RecipientNotAllowed {})
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates `addresses` and appends the new ones to `allowed`, up to MAX_ALLOWED_OWNERS
+fn add_allowed_owners(
+    deps: Deps,
+    mut allowed: Vec<Addr>,
+    addresses: Vec<String>,
+) -> Result<Vec<Addr>, ContractError> {
+    for address in addresses {
+        let address = deps.api.addr_validate(&address)?;
+        if !allowed.contains(&address) {
+            allowed.push(address);
+        }
+    }
+    if allowed.len() > MAX_ALLOWED_OWNERS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "allowed_owners must not exceed {} addresses",
+            MAX_ALLOWED_OWNERS
+        ))));
+    }
+    Ok(allowed)
+}
+
+/// Rejects an address the admin blacklisted
+fn ensure_not_blacklisted(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if BLACKLIST.may_load(storage, address)?.is_some() {
+        return Err(ContractError::Blacklisted {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Rejects changing hands while the option's transfer lock is in place
+fn ensure_unlocked(state: &State, env: &Env) -> Result<(), ContractError> {
+    match state.transfer_lock_until {
+        Some(until) if env.block.height < until => Err(ContractError::TransferLocked { until }),
+        _ => Ok(()),
+    }
+}
+
+/// Rejects selling the option once expired, when it can no longer be executed
+fn ensure_not_expired(state: &State, env: &Env) -> Result<(), ContractError> {
+    if env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+    Ok(())
+}
+
+/// Returns whether `spender` holds an unexpired approval for the option
+fn is_approved(storage: &dyn Storage, env: &Env, id: u64, spender: &Addr) -> StdResult<bool> {
+    Ok(matches!(
+        APPROVALS.may_load(storage, (id.into(), spender))?,
+        Some(expires) if !expires.is_expired(&env.block)
+    ))
+}
+
+pub fn try_propose_transfer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: String,
+    valid_until: Expiration,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the recipient is a valid address other than the contract itself
+    let recipient = validate_recipient(deps.as_ref(), &_env, &recipient)?;
+    ensure_allowed_owner(&state, &recipient)?;
+    ensure_not_blacklisted(deps.storage, &recipient)?;
+    // ensure the proposal can still be accepted
+    if valid_until.is_expired(&_env.block) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot propose an expired transfer",
+        )));
+    }
+    // ensure only one live proposal at a time
+    if load_pending_transfer(deps.storage, &_env, id)?.is_some() {
+        return Err(ContractError::TransferPending {});
+    }
+
+    PENDING_TRANSFERS.save(
+        deps.storage,
+        id.into(),
+        &PendingTransfer {
+            recipient: recipient.clone(),
+            valid_until,
+        },
+    )?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "propose_transfer");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("recipient", recipient);
+    Ok(res)
+}
+
+pub fn try_accept_transfer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is the proposed recipient
+    let pending = load_pending_transfer(deps.storage, &_env, id)?
+        .ok_or(ContractError::NoTransferPending {})?;
+    if info.sender != pending.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_not_blacklisted(deps.storage, &pending.recipient)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "accept_transfer");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("from", state.owner.as_str());
+    res.add_attribute("to", pending.recipient.as_str());
+    set_owner(deps.storage, &mut res, id, &mut state, pending.recipient)?;
+    Ok(res)
+}
+
+pub fn try_cancel_transfer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if load_pending_transfer(deps.storage, &_env, id)?.is_none() {
+        return Err(ContractError::NoTransferPending {});
+    }
+
+    PENDING_TRANSFERS.remove(deps.storage, id.into());
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "cancel_transfer");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+pub fn try_cleanup_proposals(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let expired: Vec<Vec<u8>> = PENDING_TRANSFERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, pending)) => pending.valid_until.is_expired(&_env.block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<StdResult<_>>()?;
+    for key in expired.iter() {
+        PENDING_TRANSFERS.remove(deps.storage, parse_id(key)?.into());
+    }
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "cleanup_proposals");
+    res.add_attribute("removed", expired.len().to_string());
+    Ok(res)
+}
+
+/// Loads the transfer proposal of an option, treating an expired one as absent
+fn load_pending_transfer(
+    storage: &dyn Storage,
+    env: &Env,
+    id: u64,
+) -> StdResult<Option<PendingTransfer>> {
+    Ok(PENDING_TRANSFERS
+        .may_load(storage, id.into())?
+        .filter(|pending| !pending.valid_until.is_expired(&env.block)))
+}
+
+/// Validates a new owner, which must not be the contract itself
+fn validate_recipient(deps: Deps, env: &Env, recipient: &str) -> Result<Addr, ContractError> {
+    let recipient = deps.api.addr_validate(recipient)?;
+    if recipient == env.contract.address {
+        return Err(ContractError::CannotTransferToSelf {});
+    }
+    Ok(recipient)
+}
+
+/// Transfers the option to `owner`, marking it transferred unless it goes to its creator
+fn set_owner(
+    storage: &mut dyn Storage,
+    res: &mut Response,
+    id: u64,
+    state: &mut State,
+    owner: Addr,
+) -> StdResult<()> {
+    state.transferred |= owner != state.creator;
+    hand_over(storage, res, id, state, owner)
+}
+
+/// Takes the option into the contract's custody, for an auction or on behalf of a group or
+/// shareholders. Not a transfer by itself, so an option that comes back to its creator
+/// unsold can still be reclaimed
+fn hold_option(
+    storage: &mut dyn Storage,
+    res: &mut Response,
+    id: u64,
+    state: &mut State,
+    env: &Env,
+) -> StdResult<()> {
+    hand_over(storage, res, id, state, env.contract.address.clone())
+}
+
+/// Hands the option to `owner`, dropping what the previous owner had granted or pending:
+/// approvals, a proposed transfer, and any extension, whose incentive is refunded
+fn hand_over(
+    storage: &mut dyn Storage,
+    res: &mut Response,
+    id: u64,
+    state: &mut State,
+    owner: Addr,
+) -> StdResult<()> {
+    state.owner = owner;
+    state.reminder_blocks = None;
+    state.premium = None;
+    state.ask = None;
+    state.cw20_ask = None;
+    state.auction = None;
+    state.bundle = None;
+    state.private_to = None;
+    state.memo = None;
+    state.shares = None;
+    save_option(storage, id, state)?;
+    let spenders: Vec<Vec<u8>> = APPROVALS
+        .prefix(id.into())
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+    for spender in spenders {
+        APPROVALS.remove(
+            storage,
+            (id.into(), &Addr::unchecked(parse_addr(&spender)?)),
+        );
+    }
+    let executors: Vec<Vec<u8>> = EXECUTORS
+        .prefix(id.into())
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+    for executor in executors {
+        EXECUTORS.remove(
+            storage,
+            (id.into(), &Addr::unchecked(parse_addr(&executor)?)),
+        );
+    }
+    PENDING_TRANSFERS.remove(storage, id.into());
+    refund_extension(storage, res, id)
+}
+
+pub fn try_execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteOptionMsg,
+) -> Result<Response, ContractError> {
+    // get state
+    let id = msg.id.unwrap_or(SINGLETON_ID);
+    let state: State = load_option(deps.storage, id)?;
+    // ensure there is collateral to release
+    if !has_collateral(&state) {
+        return Err(ContractError::NoCollateral {});
+    }
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure a custom collateral recipient is valid, unless the creator locked recipients
+    let collateral_recipient = match &msg.collateral_recipient {
+        Some(recipient) if !state.lock_recipients => deps.api.addr_validate(recipient.as_str())?,
+        _ => default_recipient(&_env, &state, &info.sender),
+    };
+    // ensure msg.sender is owner, an executor, or a group with enough approvals
+    authorize_executor(
+        deps.storage,
+        id,
+        &state,
+        &info.sender,
+        &collateral_recipient,
+    )?;
+    // ensure an executor, acting on the owner's behalf, does not redirect the collateral
+    if msg.collateral_recipient.is_some()
+        && info.sender != state.owner
+        && EXECUTORS
+            .may_load(deps.storage, (id.into(), &info.sender))?
+            .is_some()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure this is not a replay
+    record_execution_id(deps.storage, msg.execution_id, _env.block.height)?;
+
+    // ensure the option still has the terms the sender expects
+    if let Some(expected_owner) = msg.expected_owner {
+        if state.owner.as_str() != expected_owner {
+            return Err(ContractError::StateChanged {});
+        }
+    }
+    if let Some(expected_counter_offer) = msg.expected_counter_offer {
+        if normalize_coins(expected_counter_offer)? != normalize_coins(state.counter_offer.clone())?
+        {
+            return Err(ContractError::TermsChanged {});
+        }
+    }
+
+    // ensure sending proper counter_offer, up front under strict_order
+    if msg.strict_order {
+        ensure_counter_offer(&info.funds, &state.counter_offer)?;
+    }
+
+    // ensure not expired
+    if _env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+    // ensure still within the exercise window
+    if exercise_closed(&state, _env.block.height) {
+        return Err(ContractError::ExerciseWindowClosed {});
+    }
+
+    // ensure sending proper counter_offer
+    if !msg.strict_order {
+        ensure_counter_offer(&info.funds, &state.counter_offer)?;
+    }
+
+    let payouts = collateral_payouts(&state, collateral_recipient.clone())?;
+    // ensure no payout goes to a blacklisted address
+    ensure_not_blacklisted(deps.storage, &state.owner)?;
+    ensure_not_blacklisted(deps.storage, &state.creator)?;
+    for (recipient, _) in payouts.iter() {
+        ensure_not_blacklisted(deps.storage, recipient)?;
+    }
+
+    // keep a record of the execution
+    record_execution(
+        deps.storage,
+        id,
+        &state,
+        info.sender.clone(),
+        _env.block.height,
+    )?;
+
+    // messages go out in a fixed order tooling relies on:
+    // counter_offer to creator, then collateral to owner
+    let mut res: Response = Response::new();
+    add_settle_hook(&mut res, id, &state, SettleOutcome::Executed)?;
+    if let Some(msg) = maybe_bank_send(&state.creator, state.counter_offer) {
+        res.add_message(msg);
+    }
+
+    // release collateral to the owner, or where the sender asked
+    for (recipient, amount) in payouts {
+        if let Some(msg) = maybe_bank_send(&recipient, amount) {
+            res.add_message(msg);
+        }
+    }
+    if let Some(msg) = maybe_cw20_send(&collateral_recipient, state.cw20_collateral)? {
+        res.add_message(msg);
+    }
+
+    // delete the option
+    options().remove(deps.storage, id.into())?;
+    refund_extension(deps.storage, &mut res, id)?;
+
+    res.add_attribute("action", "execute");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Who is paid the collateral on execution unless another recipient is named: the owner,
+/// or the executing member while the contract holds the option for a group. An executor
+/// only executes on the owner's behalf
+fn default_recipient(env: &Env, state: &State, sender: &Addr) -> Addr {
+    if state.owner == env.contract.address {
+        sender.clone()
+    } else {
+        state.owner.clone()
+    }
+}
+
+/// Whether the option holds any collateral, native or cw20
+fn has_collateral(state: &State) -> bool {
+    !state.collateral.is_empty() || state.cw20_collateral.is_some()
+}
+
+/// Ensures the option is not backed by cw20 collateral, which cannot be split
+fn ensure_native_collateral(state: &State) -> Result<(), ContractError> {
+    if state.cw20_collateral.is_some() {
+        return Err(ContractError::Cw20Collateral {});
+    }
+    Ok(())
+}
+
+/// Who receives the native collateral on execution: `recipient`, or every holder of a
+/// shared option in proportion to its shares. Rounding leftovers go to the first holder
+fn collateral_payouts(state: &State, recipient: Addr) -> StdResult<Vec<(Addr, Vec<Coin>)>> {
+    let shares = match &state.shares {
+        Some(shares) => shares,
+        None => return Ok(vec![(recipient, state.collateral.clone())]),
+    };
+    let total = total_shares(shares)?;
+    let mut payouts: Vec<(Addr, Vec<Coin>)> = shares
+        .iter()
+        .map(|(holder, _)| (holder.clone(), vec![]))
+        .collect();
+    for coin in state.collateral.iter() {
+        let mut left = coin.amount;
+        for (i, (_, weight)) in shares.iter().enumerate().rev() {
+            let amount = if i == 0 {
+                left
+            } else {
+                apply_fraction(coin.amount, (*weight).into(), total.into(), Rounding::Floor)?
+            };
+            left = left.checked_sub(amount)?;
+            if !amount.is_zero() {
+                payouts[i].1.push(Coin {
+                    denom: coin.denom.clone(),
+                    amount,
+                });
+            }
+        }
+    }
+    Ok(payouts)
+}
+
+/// Whether the owner could execute the option at `height`
+fn is_executable(state: &State, height: u64) -> bool {
+    height < state.expires
+        && !exercise_closed(state, height)
+        && !state.paused
+        && has_collateral(state)
+}
+
+/// Whether the option is past its exercise deadline, though possibly not yet expired
+fn exercise_closed(state: &State, height: u64) -> bool {
+    matches!(state.exercise_deadline, Some(deadline) if height > deadline)
+}
+
+pub fn try_execute_lenient(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure there is collateral to release
+    if !has_collateral(&state) {
+        return Err(ContractError::NoCollateral {});
+    }
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner, an executor, or a group with enough approvals
+    let recipient = default_recipient(&_env, &state, &info.sender);
+    authorize_executor(deps.storage, id, &state, &info.sender, &recipient)?;
+
+    // ensure not expired
+    if _env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+    // ensure still within the exercise window
+    if exercise_closed(&state, _env.block.height) {
+        return Err(ContractError::ExerciseWindowClosed {});
+    }
+
+    // ensure sending at least the counter_offer
+    let surplus = funds_surplus(&info.funds, &state.counter_offer)?;
+    let payouts = collateral_payouts(&state, recipient.clone())?;
+    // ensure no payout goes to a blacklisted address
+    ensure_not_blacklisted(deps.storage, &state.owner)?;
+    ensure_not_blacklisted(deps.storage, &state.creator)?;
+    for (recipient, _) in payouts.iter() {
+        ensure_not_blacklisted(deps.storage, recipient)?;
+    }
+
+    // keep a record of the execution
+    record_execution(
+        deps.storage,
+        id,
+        &state,
+        info.sender.clone(),
+        _env.block.height,
+    )?;
+
+    // messages go out in a fixed order tooling relies on:
+    // counter_offer to creator, then collateral to owner
+    let mut res: Response = Response::new();
+    add_settle_hook(&mut res, id, &state, SettleOutcome::Executed)?;
+    if let Some(msg) = maybe_bank_send(&state.creator, state.counter_offer) {
+        res.add_message(msg);
+    }
+
+    // release collateral to the owner
+    for (recipient, amount) in payouts {
+        if let Some(msg) = maybe_bank_send(&recipient, amount) {
+            res.add_message(msg);
+        }
+    }
+    if let Some(msg) = maybe_cw20_send(&recipient, state.cw20_collateral)? {
+        res.add_message(msg);
+    }
+
+    // refund overpayment to sender
+    if !surplus.is_empty() {
+        res.add_message(bank_send(&info.sender, surplus));
+    }
+
+    // delete the option
+    options().remove(deps.storage, id.into())?;
+    refund_extension(deps.storage, &mut res, id)?;
+
+    res.add_attribute("action", "execute");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+pub fn try_burn(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+
+    // a member of the group owning the option, or a shareholder, burns as its owner,
+    // given enough approvals
+    let group = GROUPS.may_load(deps.storage, id.into())?;
+    let burner = if matches!(group, Some(group) if group.members.contains(&info.sender))
+        || is_shareholder(&state, &info.sender)
+    {
+        authorize_owner(
+            deps.storage,
+            id,
+            &state,
+            &info.sender,
+            &GroupAction::Burn {},
+        )?;
+        state.owner.clone()
+    } else {
+        info.sender.clone()
+    };
+
+    // ensure sender may burn and split off any keeper bounty
+    let (refund, bounty) = burn_payouts(&state, &settings, &burner, _env.block.height)?;
+
+    // ensure sending no funds
+    nonpayable(&info)?;
+    burn_option(deps.storage, id, &state, &info.sender, refund, bounty)
+}
+
+pub fn try_poke(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+    // ensure the creator opted in
+    if !state.auto_burn {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure expired, burning as the creator to refund all the collateral
+    let (refund, bounty) = burn_payouts(&state, &settings, &state.creator, _env.block.height)?;
+    burn_option(deps.storage, id, &state, &info.sender, refund, bounty)
+}
+
+/// Deletes a burned option, refunding the creator and paying `burner` any keeper bounty
+fn burn_option(
+    storage: &mut dyn Storage,
+    id: u64,
+    state: &State,
+    burner: &Addr,
+    refund: Vec<Coin>,
+    bounty: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let mut res: Response = Response::new();
+    add_settle_hook(&mut res, id, state, SettleOutcome::Burned)?;
+    res.add_attribute("action", "burn");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("burner", burner.as_str());
+    res.add_attribute("creator", state.creator.as_str());
+    res.add_attribute("refunded", coins_to_string(&refund));
+
+    // release collateral to creator
+    if let Some(msg) = maybe_bank_send(&state.creator, refund) {
+        res.add_message(msg);
+    }
+    if let Some(msg) = maybe_cw20_send(&state.creator, state.cw20_collateral.clone())? {
+        res.add_message(msg);
+    }
+
+    // pay the keeper
+    if !bounty.is_empty() {
+        res.add_attribute("bounty", coins_to_string(&bounty));
+    }
+    if let Some(msg) = maybe_bank_send(burner, bounty) {
+        res.add_message(msg);
+    }
+
+    // delete the option
+    options().remove(storage, id.into())?;
+    refund_extension(storage, &mut res, id)?;
+
+    Ok(res)
+}
+
+pub fn try_burn_many(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    // ensure a bounded batch of distinct ids
+    let mut unique = ids.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    if ids.is_empty() || ids.len() > MAX_BURN_BATCH || unique.len() != ids.len() {
+        return Err(ContractError::InvalidBatch {
+            max: MAX_BURN_BATCH,
+        });
+    }
+
+    // ensure sending no funds
+    nonpayable(&info)?;
+
+    // validate every option before touching any of them
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+    let mut payouts: Vec<(Addr, Vec<Coin>)> = vec![];
+    let mut burned: Vec<(u64, State)> = vec![];
+    for id in ids.iter() {
+        let state: State = load_option(deps.storage, *id)?;
+        let (refund, bounty) = burn_payouts(&state, &settings, &info.sender, _env.block.height)?;
+        add_payout(&mut payouts, &state.creator, refund)?;
+        add_payout(&mut payouts, &info.sender, bounty)?;
+        burned.push((*id, state));
+    }
+
+    // delete the options, refunding pending extensions
+    for id in ids.iter() {
+        options().remove(deps.storage, (*id).into())?;
+        if let Some(extension) = EXTENSIONS.may_load(deps.storage, (*id).into())? {
+            EXTENSIONS.remove(deps.storage, (*id).into());
+            add_payout(&mut payouts, &extension.proposer, extension.incentive)?;
+        }
+    }
+
+    // release merged refunds, one send per recipient
+    let mut res: Response = Response::new();
+    for (id, state) in burned.iter() {
+        add_settle_hook(&mut res, *id, state, SettleOutcome::Burned)?;
+    }
+    for (recipient, amount) in payouts {
+        if let Some(msg) = maybe_bank_send(&recipient, amount) {
+            res.add_message(msg);
+        }
+    }
+    for (_, state) in burned.iter() {
+        if let Some(msg) = maybe_cw20_send(&state.creator, state.cw20_collateral.clone())? {
+            res.add_message(msg);
+        }
+    }
+
+    res.add_attribute("action", "burn_many");
+    res.add_attribute("count", ids.len().to_string());
+    Ok(res)
+}
+
+pub fn try_reclaim(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is creator and still owner
+    if info.sender != state.creator || info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the option never left the creator's hands
+    if state.transferred {
+        return Err(ContractError::Transferred {});
+    }
+
+    // ensure sending no funds
+    nonpayable(&info)?;
+
+    // release collateral to creator
+    let mut res: Response = Response::new();
+    add_settle_hook(&mut res, id, &state, SettleOutcome::Reclaimed)?;
+    if let Some(msg) = maybe_bank_send(&state.creator, state.collateral) {
+        res.add_message(msg);
+    }
+    if let Some(msg) = maybe_cw20_send(&state.creator, state.cw20_collateral)? {
+        res.add_message(msg);
+    }
+
+    // delete the option
+    options().remove(deps.storage, id.into())?;
+    refund_extension(deps.storage, &mut res, id)?;
+
+    res.add_attribute("action", "reclaim");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+pub fn try_relinquish(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // hand the option back to the creator
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "relinquish");
+    res.add_attribute("option_id", id.to_string());
+    let creator = state.creator.clone();
+    set_owner(deps.storage, &mut res, id, &mut state, creator)?;
+    Ok(res)
+}
+
+pub fn try_renounce(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the option left the creator's hands
+    if state.owner == state.creator {
+        return Err(ContractError::NotTransferred {});
+    }
+
+    // ensure sending no funds
+    nonpayable(&info)?;
+
+    // ensure not expired
+    if _env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+
+    // release collateral to creator
+    let mut res: Response = Response::new();
+    add_settle_hook(&mut res, id, &state, SettleOutcome::Renounced)?;
+    if let Some(msg) = maybe_bank_send(&state.creator, state.collateral) {
+        res.add_message(msg);
+    }
+    if let Some(msg) = maybe_cw20_send(&state.creator, state.cw20_collateral)? {
+        res.add_message(msg);
+    }
+
+    // delete the option
+    options().remove(deps.storage, id.into())?;
+    refund_extension(deps.storage, &mut res, id)?;
+
+    res.add_attribute("action", "renounce");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Ensures `sender` may burn the option at `height` and splits its collateral
+/// into (creator refund, keeper bounty)
+fn burn_payouts(
+    state: &State,
+    settings: &Settings,
+    sender: &Addr,
+    height: u64,
+) -> Result<(Vec<Coin>, Vec<Coin>), ContractError> {
+    // ensure msg.sender is creator or owner, unless burning is open to keepers
+    let keeper = *sender != state.creator && *sender != state.owner;
+    if keeper && !settings.permissionless_burn {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // ensure not expired
+    if height < state.expires {
+        return Err(ContractError::NotExpired {
+            expires: state.expires,
+            current_height: height,
+        });
+    }
+
+    // ensure keepers wait out the grace period
+    let grace_end = state.expires.saturating_add(settings.grace_blocks);
+    if keeper && height < grace_end {
+        return Err(ContractError::GracePeriod { until: grace_end });
+    }
+
+    // split off the keeper bounty
+    let bounty_bps = if keeper {
+        settings.keeper_bounty_bps
+    } else {
+        0
+    };
+    Ok(split_bounty(state.collateral.clone(), bounty_bps)?)
+}
+
+/// Loads an option, failing with OptionNotFound once it was settled or never existed
+fn load_option(storage: &dyn Storage, id: u64) -> Result<State, ContractError> {
+    options()
+        .may_load(storage, id.into())?
+        .ok_or(ContractError::OptionNotFound { id })
+}
+
+/// Saves the option, checking in debug builds that all its coins are canonical
+fn save_option(storage: &mut dyn Storage, id: u64, state: &State) -> StdResult<()> {
+    debug_assert!(
+        state_coins(state)
+            .iter()
+            .all(|coins| assert_canonical(coins).is_ok()),
+        "option {} holds non-canonical coins",
+        id
+    );
+    options().save(storage, id.into(), state)
+}
+
+/// Every list of coins kept in an option
+fn state_coins(state: &State) -> Vec<&[Coin]> {
+    let mut coins: Vec<&[Coin]> = vec![&state.collateral, &state.counter_offer];
+    coins.extend(state.premium.as_deref());
+    coins.extend(state.ask.as_deref());
+    if let Some(auction) = &state.auction {
+        coins.push(&auction.start_price);
+        coins.push(&auction.end_price);
+    }
+    coins
+}
+
+/// Ensures `coins` are sorted by denom, with no denom twice and no zero amount,
+/// the form every amount is kept in
+pub fn assert_canonical(coins: &[Coin]) -> Result<(), ContractError> {
+    let sorted = coins.windows(2).all(|pair| pair[0].denom < pair[1].denom);
+    if !sorted || coins.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::NonCanonicalCoins {});
+    }
+    Ok(())
+}
+
+/// Notifies the creator's settle hook, if any, under the option id as reply id.
+/// Only a failure replies, so the settlement stands either way
+fn add_settle_hook(
+    res: &mut Response,
+    id: u64,
+    state: &State,
+    outcome: SettleOutcome,
+) -> StdResult<()> {
+    if let Some(hook) = &state.on_settle {
+        let counter_offer = match outcome {
+            SettleOutcome::Executed => state.counter_offer.clone(),
+            _ => vec![],
+        };
+        let msg = SettleHookMsg::OptionSettled(OptionSettledMsg {
+            option_id: id,
+            outcome,
+            counter_offer,
+            collateral: state.collateral.clone(),
+            msg_prefix: hook.msg_prefix.clone(),
+        });
+        res.add_submessage(
+            id,
+            WasmMsg::Execute {
+                contract_addr: hook.contract.to_string(),
+                msg: to_binary(&msg)?,
+                send: vec![],
+            },
+            None,
+            ReplyOn::Error,
+        );
+    }
+    Ok(())
+}
+
+/// Builds a send with its coins sorted by denom, so emitted messages are canonical
+fn bank_send(to_address: &Addr, mut amount: Vec<Coin>) -> BankMsg {
+    amount.sort_by(|a, b| a.denom.cmp(&b.denom));
+    BankMsg::Send {
+        to_address: to_address.to_string(),
+        amount,
+    }
+}
+
+/// Like bank_send, but leaves out zero coins and returns None if nothing is left to send,
+/// as some chains reject empty sends
+fn maybe_bank_send(to: &Addr, mut amount: Vec<Coin>) -> Option<CosmosMsg> {
+    amount.retain(|coin| !coin.amount.is_zero());
+    if amount.is_empty() {
+        return None;
+    }
+    Some(bank_send(to, amount).into())
+}
+
+/// Pays out the cw20 collateral of an option, if any
+fn maybe_cw20_send(
+    to: &Addr,
+    collateral: Option<Cw20CoinVerified>,
+) -> StdResult<Option<CosmosMsg>> {
+    collateral
+        .map(|collateral| send_balance(to, collateral.into()))
+        .transpose()
+}
+
+/// Pays out `balance`, by bank send for native coins or by cw20 transfer for a token
+fn send_balance(to: &Addr, balance: Balance) -> StdResult<CosmosMsg> {
+    match balance {
+        Balance::Native(coins) => Ok(bank_send(to, coins.0).into()),
+        Balance::Cw20(coin) => Ok(WasmMsg::Execute {
+            contract_addr: coin.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: to.to_string(),
+                amount: coin.amount,
+            })?,
+            send: vec![],
+        }
+        .into()),
+    }
+}
+
+/// Whether both are native coins, or both amounts of the same cw20 token
+fn same_asset(a: &Balance, b: &Balance) -> bool {
+    match (a, b) {
+        (Balance::Native(_), Balance::Native(_)) => true,
+        (Balance::Cw20(a), Balance::Cw20(b)) => a.address == b.address,
+        _ => false,
+    }
+}
+
+fn balance_to_string(balance: &Balance) -> String {
+    match balance {
+        Balance::Native(coins) => coins_to_string(&coins.0),
+        Balance::Cw20(coin) => format!("{}{}", coin.amount, coin.address),
+    }
+}
+
+/// Renders coins for attributes, e.g. "975BTC,30ATOM"
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Adds `amount` to what is owed to `recipient`, merging coins of the same denom
+fn add_payout(
+    payouts: &mut Vec<(Addr, Vec<Coin>)>,
+    recipient: &Addr,
+    amount: Vec<Coin>,
+) -> StdResult<()> {
+    if amount.is_empty() {
+        return Ok(());
+    }
+    let index = match payouts.iter().position(|(addr, _)| addr == recipient) {
+        Some(index) => index,
+        None => {
+            payouts.push((recipient.clone(), vec![]));
+            payouts.len() - 1
+        }
+    };
+    add_coins(&mut payouts[index].1, amount)
+}
+
+/// Collapses coins sharing a denom into one, keeping first-seen order
+fn merge_denoms(coins: Vec<Coin>) -> StdResult<Vec<Coin>> {
+    let mut merged = vec![];
+    add_coins(&mut merged, coins)?;
+    Ok(merged)
+}
+
+/// Merges coins sharing a denom and sorts them, so equal amounts compare equal
+fn normalize_coins(coins: Vec<Coin>) -> StdResult<Vec<Coin>> {
+    let mut normalized = merge_denoms(coins)?;
+    normalized.sort_by(|a, b| a.denom.cmp(&b.denom));
+    Ok(normalized)
+}
+
+/// Normalizes coins and drops zero amounts, see assert_canonical
+fn canonical_coins(coins: Vec<Coin>) -> StdResult<Vec<Coin>> {
+    let mut canonical = normalize_coins(coins)?;
+    canonical.retain(|coin| !coin.amount.is_zero());
+    Ok(canonical)
+}
+
+pub fn try_merge(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    other_id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    if id == other_id {
+        return Err(ContractError::Std(StdError::generic_err(
+            "cannot merge an option into itself",
+        )));
+    }
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    let other: State = load_option(deps.storage, other_id)?;
+    // ensure msg.sender owns both
+    if info.sender != state.owner || info.sender != other.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure not paused
+    if state.paused || other.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure only the amounts differ
+    if !same_terms(&state, &other) {
+        return Err(ContractError::IncompatibleOptions {});
+    }
+
+    add_coins(&mut state.collateral, other.collateral)?;
+    state.collateral.sort_by(|a, b| a.denom.cmp(&b.denom));
+    add_coins(&mut state.counter_offer, other.counter_offer)?;
+    state.counter_offer.sort_by(|a, b| a.denom.cmp(&b.denom));
+    // the listing was for the option before the merge
+    state.premium = None;
+    state.ask = None;
+    state.cw20_ask = None;
+    state.auction = None;
+    state.bundle = None;
+    state.private_to = None;
+    save_option(deps.storage, id, &state)?;
+
+    // remove the other option
+    options().remove(deps.storage, other_id.into())?;
+    PENDING_TRANSFERS.remove(deps.storage, other_id.into());
+    let mut res: Response = Response::new();
+    refund_extension(deps.storage, &mut res, other_id)?;
+
+    res.add_attribute("action", "merge");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("merged_id", other_id.to_string());
+    res.add_attribute("collateral", coins_to_string(&state.collateral));
+    Ok(res)
+}
+
+/// Whether two options differ at most in their collateral and counter_offer amounts
+fn same_terms(a: &State, b: &State) -> bool {
+    let mut a_denoms: Vec<&str> = a.counter_offer.iter().map(|c| c.denom.as_str()).collect();
+    let mut b_denoms: Vec<&str> = b.counter_offer.iter().map(|c| c.denom.as_str()).collect();
+    a_denoms.sort_unstable();
+    b_denoms.sort_unstable();
+    a_denoms == b_denoms
+        && a.creator == b.creator
+        && a.expires == b.expires
+        && a.exercise_deadline == b.exercise_deadline
+        && a.rounding == b.rounding
+        && a.on_settle == b.on_settle
+        && a.transferable == b.transferable
+        && a.transfer_lock_until == b.transfer_lock_until
+        && a.transferred == b.transferred
+        && a.allowed_owners == b.allowed_owners
+        && a.lock_recipients == b.lock_recipients
+        && a.royalty_bps == b.royalty_bps
+        && a.auto_burn == b.auto_burn
+        && a.cw20_collateral.is_none()
+        && b.cw20_collateral.is_none()
+}
+
+/// Adds `amount` to `total`, merging coins of the same denom, failing on overflow
+fn add_coins(total: &mut Vec<Coin>, amount: Vec<Coin>) -> StdResult<()> {
+    for coin in amount {
+        match total.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => {
+                existing.amount = existing
+                    .amount
+                    .checked_add(coin.amount)
+                    .map_err(StdError::from)?
+            }
+            None => total.push(coin),
+        }
+    }
+    Ok(())
+}
+
+/// Native coins the contract owes: the collateral of live options but `except`, pending
+/// extension incentives, escrowed bids and auction high bids
+fn native_obligations(storage: &dyn Storage, except: Option<u64>) -> StdResult<Vec<Coin>> {
+    let mut owed = vec![];
+    for item in options().range(storage, None, None, Order::Ascending) {
+        let (key, state) = item?;
+        if Some(parse_id(&key)?) != except {
+            add_coins(&mut owed, state.collateral)?;
+        }
+    }
+    for item in EXTENSIONS.range(storage, None, None, Order::Ascending) {
+        let (_, extension) = item?;
+        add_coins(&mut owed, extension.incentive)?;
+    }
+    for item in BIDS.range(storage, None, None, Order::Ascending) {
+        let (_, bid) = item?;
+        if let Balance::Native(amount) = bid.amount {
+            add_coins(&mut owed, amount.0)?;
+        }
+    }
+    for item in AUCTIONS.range(storage, None, None, Order::Ascending) {
+        let (_, auction) = item?;
+        if let Some(bid) = auction.high_bid {
+            add_coins(&mut owed, vec![bid.amount])?;
+        }
+    }
+    Ok(owed)
+}
+
+/// Amount of `token` the contract owes: the collateral of live options but `except` and
+/// escrowed bids in it
+fn cw20_obligations(storage: &dyn Storage, token: &Addr, except: u64) -> StdResult<Uint128> {
+    let mut owed = Uint128::zero();
+    for item in options().range(storage, None, None, Order::Ascending) {
+        let (key, state) = item?;
+        match state.cw20_collateral {
+            Some(collateral) if collateral.address == *token && parse_id(&key)? != except => {
+                owed = owed.checked_add(collateral.amount)?;
+            }
+            _ => {}
+        }
+    }
+    for item in BIDS.range(storage, None, None, Order::Ascending) {
+        let (_, bid) = item?;
+        match bid.amount {
+            Balance::Cw20(coin) if coin.address == *token => {
+                owed = owed.checked_add(coin.amount)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(owed)
+}
+
+/// What of `balance` is left beyond `owed`, per denom
+fn coins_beyond(balance: Vec<Coin>, owed: &[Coin]) -> StdResult<Vec<Coin>> {
+    let mut left = vec![];
+    for coin in balance {
+        let owed = owed
+            .iter()
+            .find(|c| c.denom == coin.denom)
+            .map(|c| c.amount)
+            .unwrap_or_default();
+        if coin.amount > owed {
+            left.push(Coin {
+                amount: coin.amount.checked_sub(owed)?,
+                denom: coin.denom,
+            });
+        }
+    }
+    Ok(left)
+}
+
+/// Rejects any funds sent with a message that does not take payment
+fn nonpayable(info: &MessageInfo) -> Result<(), ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::FundsNotAllowed {
+            received: info.funds.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Rejects funds that differ from the counter_offer in any denom, listing what is short and extra
+fn ensure_counter_offer(funds: &[Coin], counter_offer: &[Coin]) -> Result<(), ContractError> {
+    let mut extra = merge_denoms(funds.to_vec())?;
+    let mut missing = vec![];
+    for coin in counter_offer {
+        match extra.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(paid) if paid.amount >= coin.amount => {
+                paid.amount = paid
+                    .amount
+                    .checked_sub(coin.amount)
+                    .map_err(StdError::from)?;
+            }
+            Some(paid) => {
+                missing.push(Coin {
+                    denom: coin.denom.clone(),
+                    amount: coin
+                        .amount
+                        .checked_sub(paid.amount)
+                        .map_err(StdError::from)?,
+                });
+                paid.amount = Uint128::zero();
+            }
+            None => missing.push(coin.clone()),
+        }
+    }
+    extra.retain(|c| !c.amount.is_zero());
+    if !missing.is_empty() || !extra.is_empty() {
+        return Err(ContractError::CounterOfferMismatch { missing, extra });
+    }
+    Ok(())
+}
+
+/// Returns what `funds` holds beyond `required`, failing if any required denom is short
+fn funds_surplus(funds: &[Coin], required: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let mut surplus = funds.to_vec();
+    for coin in required {
+        let paid = surplus
+            .iter_mut()
+            .find(|c| c.denom == coin.denom && c.amount >= coin.amount)
+            .ok_or_else(|| {
+                StdError::generic_err(format!("must send at least counter_offer: {:?}", required))
+            })?;
+        paid.amount = paid
+            .amount
+            .checked_sub(coin.amount)
+            .map_err(StdError::from)?;
+    }
+    surplus.retain(|c| !c.amount.is_zero());
+    Ok(surplus)
+}
+
+/// Saves the terms of an option executed by `executor` as the last execution
+fn record_execution(
+    storage: &mut dyn Storage,
+    id: u64,
+    state: &State,
+    executor: Addr,
+    height: u64,
+) -> StdResult<()> {
+    LAST_EXECUTION.save(
+        storage,
+        &ExecutionRecord {
+            id,
+            executor,
+            height,
+            counter_offer: state.counter_offer.clone(),
+            collateral: state.collateral.clone(),
+        },
+    )
+}
+
+/// Remembers a client-supplied execution id, rejecting one that was already used
+fn record_execution_id(
+    storage: &mut dyn Storage,
+    execution_id: Option<String>,
+    height: u64,
+) -> Result<(), ContractError> {
+    if let Some(execution_id) = execution_id {
+        if EXECUTION_IDS.may_load(storage, &execution_id)?.is_some() {
+            return Err(ContractError::DuplicateExecution {});
+        }
+        EXECUTION_IDS.save(storage, &execution_id, &height)?;
+    }
+    Ok(())
+}
+
+/// Splits `bounty_bps` off each collateral coin, rounding the bounty down.
+/// Returns (remainder, bounty), leaving out bounty coins that round to zero
+fn split_bounty(collateral: Vec<Coin>, bounty_bps: u64) -> StdResult<(Vec<Coin>, Vec<Coin>)> {
+    let mut remainder = vec![];
+    let mut bounty = vec![];
+    for coin in collateral {
+        let share = apply_fraction(coin.amount, bounty_bps.into(), 10_000, Rounding::Floor)?;
+        if !share.is_zero() {
+            bounty.push(Coin {
+                denom: coin.denom.clone(),
+                amount: share,
+            });
+        }
+        remainder.push(Coin {
+            denom: coin.denom,
+            amount: coin.amount.checked_sub(share)?,
+        });
+    }
+    Ok((remainder, bounty))
+}
+
+pub fn try_propose_extension(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    new_expires: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is creator
+    if info.sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure not expired
+    if _env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+    // ensure the expiry moves forward, within the allowed duration
+    if new_expires <= state.expires {
+        return Err(ContractError::Std(StdError::generic_err(
+            "new_expires must be later than the current expiry",
+        )));
+    }
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+    ensure_duration(&settings, new_expires, _env.block.height)?;
+    // ensure only one proposal at a time
+    if EXTENSIONS.may_load(deps.storage, id.into())?.is_some() {
+        return Err(ContractError::ExtensionPending {});
+    }
+
+    EXTENSIONS.save(
+        deps.storage,
+        id.into(),
+        &Extension {
+            proposer: info.sender,
+            new_expires,
+            incentive: info.funds,
+        },
+    )?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "propose_extension");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("new_expires", new_expires.to_string());
+    Ok(res)
+}
+
+pub fn try_accept_extension(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure not expired
+    if _env.block.height >= state.expires {
+        return Err(ContractError::Expired {
+            expires: state.expires,
+        });
+    }
+    let extension = EXTENSIONS
+        .may_load(deps.storage, id.into())?
+        .ok_or(ContractError::NoExtension {})?;
+
+    // move the expiry and pay the owner
+    state.expires = extension.new_expires;
+    save_option(deps.storage, id, &state)?;
+    EXTENSIONS.remove(deps.storage, id.into());
+
+    let mut res: Response = Response::new();
+    if !extension.incentive.is_empty() {
+        res.add_message(bank_send(&state.owner, extension.incentive));
+    }
+    res.add_attribute("action", "accept_extension");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("expires_height", state.expires.to_string());
+    Ok(res)
+}
+
+pub fn try_reject_extension(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    let extension = EXTENSIONS
+        .may_load(deps.storage, id.into())?
+        .ok_or(ContractError::NoExtension {})?;
+    // ensure msg.sender is owner or the proposer
+    if info.sender != state.owner && info.sender != extension.proposer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut res: Response = Response::new();
+    refund_extension(deps.storage, &mut res, id)?;
+    res.add_attribute("action", "reject_extension");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Ensures an option expiring at `expires` does not outlast the max duration from `height`
+fn ensure_duration(settings: &Settings, expires: u64, height: u64) -> Result<(), ContractError> {
+    if let Some(max_duration) = settings.max_duration_blocks {
+        let max_expires = height.saturating_add(max_duration);
+        if expires > max_expires {
+            return Err(ContractError::DurationTooLong { max_expires });
+        }
+    }
+    Ok(())
+}
+
+/// Drops the pending extension of an option, if any, refunding the incentive to its proposer
+fn refund_extension(storage: &mut dyn Storage, res: &mut Response, id: u64) -> StdResult<()> {
+    if let Some(extension) = EXTENSIONS.may_load(storage, id.into())? {
+        EXTENSIONS.remove(storage, id.into());
+        if !extension.incentive.is_empty() {
+            res.add_message(bank_send(&extension.proposer, extension.incentive));
+        }
+    }
+    Ok(())
+}
+
+pub fn try_allow_owners(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is creator
+    if info.sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the option is restricted in the first place, appending must never widen it to a list
+    let allowed = state
+        .allowed_owners
+        .take()
+        .ok_or_else(|| StdError::generic_err("Option has no owner allowlist to append to"))?;
+
+    let added = addresses.len();
+    let allowed = add_allowed_owners(deps.as_ref(), allowed, addresses)?;
+    state.allowed_owners = Some(allowed);
+    save_option(deps.storage, id, &state)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "allow_owners");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("added", added.to_string());
+    Ok(res)
+}
+
+pub fn try_transfer_creator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is creator
+    if info.sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // ensure the new creator is a valid address other than the contract itself
+    let new_creator = validate_recipient(deps.as_ref(), &_env, &recipient)?;
+    ensure_not_blacklisted(deps.storage, &new_creator)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "transfer_creator");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("previous_creator", state.creator.as_str());
+    res.add_attribute("new_creator", new_creator.as_str());
+
+    // set new creator on state, the creator index follows on save.
+    // An option held by someone else than the new creator must not become reclaimable by them
+    state.transferred |= state.owner != new_creator;
+    state.creator = new_creator;
+    // the premium was offered by the previous creator
+    state.premium = None;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
+}
+
+pub fn try_list(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    price: Vec<Coin>,
+    private_to: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let price = validate_price(price)?;
+    let private_to = validate_private_to(deps.as_ref(), private_to)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "list");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("price", coins_to_string(&price));
+    state.ask = Some(price);
+    state.cw20_ask = None;
+    state.auction = None;
+    state.private_to = private_to;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
+}
+
+pub fn try_list_cw20(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    token: String,
+    amount: Uint128,
+    private_to: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let token = deps.api.addr_validate(&token)?;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "price must be non-empty and not zero",
+        )));
+    }
+    let private_to = validate_private_to(deps.as_ref(), private_to)?;
+    let price = Cw20CoinVerified {
+        address: token,
+        amount,
+    };
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "list");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("price", balance_to_string(&price.clone().into()));
+    state.ask = None;
+    state.cw20_ask = Some(price);
+    state.auction = None;
+    state.private_to = private_to;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
+}
+
+pub fn try_list_dutch(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    auction: DutchAuction,
+    private_to: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the price only falls, over a non-empty range of heights
+    let start_price = validate_price(auction.start_price)?;
+    let end_price = validate_price(auction.end_price)?;
+    let declining = start_price.len() == end_price.len()
+        && start_price
+            .iter()
+            .zip(end_price.iter())
+            .all(|(start, end)| start.denom == end.denom && start.amount >= end.amount);
+    if !declining {
+        return Err(ContractError::Std(StdError::generic_err(
+            "end_price must have the denoms of start_price and not exceed it",
+        )));
+    }
+    if auction.end_height <= auction.start_height {
+        return Err(ContractError::Std(StdError::generic_err(
+            "end_height must be after start_height",
+        )));
+    }
+    let private_to = validate_private_to(deps.as_ref(), private_to)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "list_dutch");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("start_price", coins_to_string(&start_price));
+    res.add_attribute("end_price", coins_to_string(&end_price));
+    state.ask = None;
+    state.cw20_ask = None;
+    state.auction = Some(DutchAuction {
+        start_price,
+        end_price,
+        ..auction
+    });
+    state.private_to = private_to;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
+}
+
+/// Price of one denom in a Dutch auction at `height`, falling linearly from `start` to
+/// `end` between the two heights. The discount is rounded down, in the seller's favour
+pub fn dutch_price(
+    start: Uint128,
+    end: Uint128,
+    start_height: u64,
+    end_height: u64,
+    height: u64,
+) -> StdResult<Uint128> {
+    if height <= start_height {
+        return Ok(start);
+    }
+    if height >= end_height {
+        return Ok(end);
+    }
+    let discount = apply_fraction(
+        start.checked_sub(end)?,
+        (height - start_height).into(),
+        (end_height - start_height).into(),
+        Rounding::Floor,
+    )?;
+    Ok(start.checked_sub(discount)?)
+}
+
+/// Current price of every denom in a Dutch auction
+fn auction_price(auction: &DutchAuction, height: u64) -> StdResult<Vec<Coin>> {
+    auction
+        .start_price
+        .iter()
+        .zip(auction.end_price.iter())
+        .map(|(start, end)| {
+            Ok(Coin {
+                denom: start.denom.clone(),
+                amount: dutch_price(
+                    start.amount,
+                    end.amount,
+                    auction.start_height,
+                    auction.end_height,
+                    height,
+                )?,
+            })
+        })
+        .collect()
+}
+
+pub fn try_update_listing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    price: Vec<Coin>,
+    private_to: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the option is listed
+    if state.ask.is_none() {
+        return Err(ContractError::NotListed {});
+    }
+    let price = validate_price(price)?;
+    let private_to = validate_private_to(deps.as_ref(), private_to)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "update_listing");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("price", coins_to_string(&price));
+    state.ask = Some(price);
+    state.private_to = private_to;
+    save_option(deps.storage, id, &state)?;
+    Ok(res)
+}
+
+pub fn try_delist(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the option is listed, at a fixed price, in a Dutch auction or at its premium,
+    // dropping every listing
+    let ask = state.ask.take();
+    let cw20_ask = state.cw20_ask.take();
+    let auction = state.auction.take();
+    let premium = state.premium.take();
+    let bundle = state.bundle.take();
+    let listed = ask.is_some()
+        || cw20_ask.is_some()
+        || auction.is_some()
+        || premium.is_some()
+        || bundle.is_some();
+    if !listed {
+        return Err(ContractError::NotListed {});
+    }
+    state.private_to = None;
+    save_option(deps.storage, id, &state)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "delist");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+/// Merges and sorts an asking price, which must not be empty or zero
+fn validate_price(price: Vec<Coin>) -> Result<Vec<Coin>, ContractError> {
+    let price = normalize_coins(price)?;
+    if price.is_empty() || price.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "price must be non-empty and not zero",
+        )));
+    }
+    Ok(price)
+}
+
+/// Validates the only buyer of a listing, if any
+fn validate_private_to(deps: Deps, private_to: Option<String>) -> StdResult<Option<Addr>> {
+    private_to
+        .map(|buyer| deps.api.addr_validate(&buyer))
+        .transpose()
+}
+
+/// Ensures the buyer is the one a private listing is for
+fn ensure_private_buyer(private_to: &Option<Addr>, buyer: &Addr) -> Result<(), ContractError> {
+    match private_to {
+        Some(private_to) if private_to != buyer => Err(ContractError::PrivateListing {}),
+        _ => Ok(()),
+    }
+}
+
+pub fn try_buy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    ensure_not_expired(&state, &_env)?;
+    if let Some(auction) = AUCTIONS.may_load(deps.storage, id.into())? {
+        return try_buy_now(deps, _env, info, id, state, auction);
+    }
+    let funds = normalize_coins(info.funds.clone())?;
+    let (price, surplus) = match (&state.ask, &state.auction) {
+        // ensure paying exactly the asking price
+        (Some(ask), _) => {
+            if funds != *ask {
+                return Err(ContractError::WrongPrice {});
+            }
+            (ask.clone(), vec![])
+        }
+        // ensure the auction started and paying at least its current price
+        (None, Some(auction)) => {
+            if _env.block.height < auction.start_height {
+                return Err(ContractError::AuctionNotStarted {
+                    start_height: auction.start_height,
+                });
+            }
+            let price = auction_price(auction, _env.block.height)?;
+            let surplus =
+                funds_surplus(&funds, &price).map_err(|_| ContractError::BelowAuctionPrice {})?;
+            (price, surplus)
+        }
+        // ensure paying in the listed token, see try_buy_cw20
+        (None, None) if state.cw20_ask.is_some() => return Err(ContractError::WrongPrice {}),
+        (None, None) => return Err(ContractError::NotListed {}),
+    };
+    ensure_private_buyer(&state.private_to, &info.sender)?;
+    // ensure the buyer may hold the option and the seller be paid
+    let buyer = validate_recipient(deps.as_ref(), &_env, info.sender.as_str())?;
+    ensure_allowed_owner(&state, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &state.owner)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "buy");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("seller", state.owner.as_str());
+    res.add_attribute("buyer", buyer.as_str());
+    res.add_attribute("price", coins_to_string(&price));
+    pay_sale(
+        deps.storage,
+        &mut res,
+        &[&state],
+        &state.owner,
+        price.into(),
+    )?;
+    if !surplus.is_empty() {
+        res.add_message(bank_send(&buyer, surplus));
+    }
+    set_owner(deps.storage, &mut res, id, &mut state, buyer)?;
+    Ok(res)
+}
+
+pub fn try_list_bundle(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    ids: Vec<u64>,
+    price: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // ensure a bundle of distinct options, within bounds
+    let mut distinct = ids.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() != ids.len() || ids.len() < 2 || ids.len() > MAX_BUNDLE_SIZE {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "bundle must hold between 2 and {} distinct options",
+            MAX_BUNDLE_SIZE
+        ))));
+    }
+    let price = validate_price(price)?;
+
+    let bundle_id = NEXT_BUNDLE_ID.may_load(deps.storage)?.unwrap_or_default();
+    for id in ids.iter() {
+        let mut state: State = load_option(deps.storage, *id)?;
+        // ensure the option may change hands
+        if !state.transferable {
+            return Err(ContractError::NonTransferable {});
+        }
+        ensure_unlocked(&state, &_env)?;
+        // ensure not paused
+        if state.paused {
+            return Err(ContractError::Paused {});
+        }
+        // ensure msg.sender is owner of every option
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        state.bundle = Some(bundle_id);
+        save_option(deps.storage, *id, &state)?;
+    }
+    BUNDLES.save(
+        deps.storage,
+        bundle_id.into(),
+        &Bundle {
+            seller: info.sender,
+            ids: ids.clone(),
+            price: price.clone(),
+        },
+    )?;
+    NEXT_BUNDLE_ID.save(deps.storage, &(bundle_id + 1))?;
+
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "list_bundle");
+    res.add_attribute("bundle_id", bundle_id.to_string());
+    res.add_attribute("option_ids", ids.join(","));
+    res.add_attribute("price", coins_to_string(&price));
+    Ok(res)
+}
+
+pub fn try_buy_bundle(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    bundle_id: u64,
+) -> Result<Response, ContractError> {
+    let bundle = BUNDLES
+        .may_load(deps.storage, bundle_id.into())?
+        .ok_or(ContractError::BundleNotFound { bundle_id })?;
+    // ensure every option is still in the bundle
+    let mut legs = vec![];
+    for id in bundle.ids.iter() {
+        let state = match options().may_load(deps.storage, (*id).into())? {
+            Some(state) if state.bundle == Some(bundle_id) => state,
+            _ => return Err(ContractError::BundleInvalid {}),
+        };
+        // ensure not paused
+        if state.paused {
+            return Err(ContractError::Paused {});
+        }
+        ensure_not_expired(&state, &_env)?;
+        legs.push(state);
+    }
+    // ensure paying exactly the bundle price
+    if normalize_coins(info.funds.clone())? != bundle.price {
+        return Err(ContractError::WrongPrice {});
+    }
+    // ensure the buyer may hold every option and the seller be paid
+    let buyer = validate_recipient(deps.as_ref(), &_env, info.sender.as_str())?;
+    for state in legs.iter() {
+        ensure_allowed_owner(state, &buyer)?;
+    }
+    ensure_not_blacklisted(deps.storage, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &bundle.seller)?;
+    BUNDLES.remove(deps.storage, bundle_id.into());
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "buy_bundle");
+    res.add_attribute("bundle_id", bundle_id.to_string());
+    res.add_attribute("seller", bundle.seller.as_str());
+    res.add_attribute("buyer", buyer.as_str());
+    res.add_attribute("price", coins_to_string(&bundle.price));
+    let sold: Vec<&State> = legs.iter().collect();
+    pay_sale(
+        deps.storage,
+        &mut res,
+        &sold,
+        &bundle.seller,
+        bundle.price.into(),
+    )?;
+    for (id, mut state) in bundle.ids.into_iter().zip(legs) {
+        set_owner(deps.storage, &mut res, id, &mut state, buyer.clone())?;
+    }
+    Ok(res)
+}
+
+pub fn try_buy_primary(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    ensure_not_expired(&state, &_env)?;
+    // ensure still offered, and paying at least the premium
+    let premium = state.premium.clone().ok_or(ContractError::NotListed {})?;
+    let funds = normalize_coins(info.funds.clone())?;
+    let surplus = funds_surplus(&funds, &premium).map_err(|_| ContractError::BelowPremium {})?;
+    // ensure the buyer may hold the option and the creator be paid
+    let buyer = validate_recipient(deps.as_ref(), &_env, info.sender.as_str())?;
+    if buyer == state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_allowed_owner(&state, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &state.creator)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "buy_primary");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("buyer", buyer.as_str());
+    res.add_attribute("premium", coins_to_string(&premium));
+    pay_sale(
+        deps.storage,
+        &mut res,
+        &[&state],
+        &state.creator,
+        premium.into(),
+    )?;
+    if !surplus.is_empty() {
+        res.add_message(bank_send(&buyer, surplus));
+    }
+    set_owner(deps.storage, &mut res, id, &mut state, buyer)?;
+    Ok(res)
+}
+
+/// Buy of an option up for an English auction, ending the auction at its buy-now price
+fn try_buy_now(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    mut state: State,
+    auction: EnglishAuction,
+) -> Result<Response, ContractError> {
+    let buy_now = auction.buy_now.ok_or(ContractError::NotListed {})?;
+    // ensure the auction still runs
+    if _env.block.height >= auction.end_height {
+        return Err(ContractError::AuctionEnded {});
+    }
+    // ensure msg.sender is not the seller
+    if info.sender == auction.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_private_buyer(&auction.private_to, &info.sender)?;
+    // ensure no bid already reached the buy-now price
+    if let Some(high_bid) = &auction.high_bid {
+        if high_bid.amount.amount >= buy_now.amount {
+            return Err(ContractError::BuyNowOutbid {});
+        }
+    }
+    // ensure paying exactly the buy-now price
+    if normalize_coins(info.funds)? != [buy_now.clone()] {
+        return Err(ContractError::WrongPrice {});
+    }
+    // ensure the buyer may hold the option and the seller be paid
+    let buyer = validate_recipient(deps.as_ref(), &_env, info.sender.as_str())?;
+    ensure_allowed_owner(&state, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &auction.seller)?;
+    AUCTIONS.remove(deps.storage, id.into());
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "buy");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("seller", auction.seller.as_str());
+    res.add_attribute("buyer", buyer.as_str());
+    res.add_attribute("price", buy_now.to_string());
+    pay_sale(
+        deps.storage,
+        &mut res,
+        &[&state],
+        &auction.seller,
+        vec![buy_now].into(),
+    )?;
+    // refund the high bid
+    if let Some(outbid) = auction.high_bid {
+        res.add_message(bank_send(&outbid.bidder, vec![outbid.amount]));
+    }
+    set_owner(deps.storage, &mut res, id, &mut state, buyer)?;
+    Ok(res)
+}
+
+pub fn try_receive_cw20(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // ensure only tokens are sent
+    nonpayable(&info)?;
+    // the token contract is the caller, paying on behalf of wrapper.sender
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let paid = Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    };
+    match from_binary(&wrapper.msg)? {
+        Cw20HookMsg::CreateOption(msg) => try_create_cw20(deps, _env, sender, msg, paid),
+        Cw20HookMsg::Buy { id } => {
+            try_buy_cw20(deps, _env, sender, id.unwrap_or(SINGLETON_ID), paid)
+        }
+        Cw20HookMsg::PlaceBid { id, valid_until } => place_bid(
+            deps,
+            _env,
+            sender,
+            id.unwrap_or(SINGLETON_ID),
+            paid.into(),
+            valid_until.unwrap_or_default(),
+        ),
+    }
+}
+
+pub fn try_buy_cw20(
+    deps: DepsMut,
+    _env: Env,
+    buyer: Addr,
+    id: u64,
+    paid: Cw20CoinVerified,
+) -> Result<Response, ContractError> {
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    let price = match &state.cw20_ask {
+        Some(price) => price.clone(),
+        None if state.ask.is_some() || state.auction.is_some() => {
+            return Err(ContractError::WrongPrice {})
+        }
+        None => return Err(ContractError::NotListed {}),
+    };
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    ensure_not_expired(&state, &_env)?;
+    // ensure paying exactly the asking price, in the listed token
+    if paid != price {
+        return Err(ContractError::WrongPrice {});
+    }
+    ensure_private_buyer(&state.private_to, &buyer)?;
+    // ensure the buyer may hold the option and the seller be paid
+    let buyer = validate_recipient(deps.as_ref(), &_env, buyer.as_str())?;
+    ensure_allowed_owner(&state, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &buyer)?;
+    ensure_not_blacklisted(deps.storage, &state.owner)?;
+
+    let price: Balance = price.into();
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "buy");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("seller", state.owner.as_str());
+    res.add_attribute("buyer", buyer.as_str());
+    res.add_attribute("price", balance_to_string(&price));
+    pay_sale(deps.storage, &mut res, &[&state], &state.owner, price)?;
+    set_owner(deps.storage, &mut res, id, &mut state, buyer)?;
+    Ok(res)
+}
+
+/// Pays `seller` for a market sale of the options in `sold`, less the market fee and the
+/// creators' royalties. Each option's royalty is taken on an equal share of the price left
+/// after the fee, and none when its creator sells
+fn pay_sale(
+    storage: &dyn Storage,
+    res: &mut Response,
+    sold: &[&State],
+    seller: &Addr,
+    price: Balance,
+) -> StdResult<()> {
+    let settings = SETTINGS.load(storage)?;
+    let (price, fee) = match &settings.treasury {
+        Some(_) => split_price(price, settings.market_fee_bps)?,
+        None => (price, Balance::default()),
+    };
+    let mut proceeds = price.clone();
+    let mut royalties = vec![];
+    for state in sold.iter().filter(|state| state.creator != *seller) {
+        let share = 10_000 * sold.len() as u128;
+        let (_, royalty) = split_fraction(price.clone(), state.royalty_bps.into(), share)?;
+        if !royalty.is_empty() {
+            proceeds = subtract_balance(proceeds, &royalty)?;
+            royalties.push((&state.creator, royalty));
+        }
+    }
+    if !proceeds.is_empty() {
+        res.add_message(send_balance(seller, proceeds.clone())?);
+    }
+    if !fee.is_empty() || !royalties.is_empty() {
+        res.add_attribute("proceeds", balance_to_string(&proceeds));
+    }
+    if let (Some(treasury), false) = (&settings.treasury, fee.is_empty()) {
+        res.add_attribute("market_fee", balance_to_string(&fee));
+        res.add_message(send_balance(treasury, fee)?);
+    }
+    for (creator, royalty) in royalties {
+        res.add_attribute("royalty", balance_to_string(&royalty));
+        res.add_message(send_balance(creator, royalty)?);
+    }
+    Ok(())
+}
+
+/// Takes `amount` out of `balance`, leaving out zero coins
+fn subtract_balance(balance: Balance, amount: &Balance) -> StdResult<Balance> {
+    match (balance, amount) {
+        (Balance::Native(balance), Balance::Native(amount)) => {
+            let mut left = vec![];
+            for coin in balance.0 {
+                let cut = amount
+                    .0
+                    .iter()
+                    .find(|c| c.denom == coin.denom)
+                    .map(|c| c.amount)
+                    .unwrap_or_default();
+                let amount = coin.amount.checked_sub(cut)?;
+                if !amount.is_zero() {
+                    left.push(Coin {
+                        denom: coin.denom,
+                        amount,
+                    });
+                }
+            }
+            Ok(left.into())
+        }
+        (Balance::Cw20(balance), Balance::Cw20(amount)) if balance.address == amount.address => {
+            Ok(Cw20CoinVerified {
+                address: balance.address,
+                amount: balance.amount.checked_sub(amount.amount)?,
+            }
+            .into())
+        }
+        _ => Err(StdError::generic_err("mismatched balances")),
+    }
+}
+
+/// Splits `bps` basis points off a sale price, rounding the cut down.
+/// Returns (rest, cut), leaving out zero coins
+pub fn split_price(price: Balance, bps: u64) -> StdResult<(Balance, Balance)> {
+    split_fraction(price, bps.into(), 10_000)
+}
+
+/// Splits numerator / denominator off a price, rounding the cut down.
+/// Returns (rest, cut), leaving out zero coins
+fn split_fraction(
+    price: Balance,
+    numerator: u128,
+    denominator: u128,
+) -> StdResult<(Balance, Balance)> {
+    match price {
+        Balance::Native(coins) => {
+            let mut rest = vec![];
+            let mut cut = vec![];
+            for coin in coins.0 {
+                let share = apply_fraction(coin.amount, numerator, denominator, Rounding::Floor)?;
+                let left = coin.amount.checked_sub(share)?;
+                if !share.is_zero() {
+                    cut.push(Coin {
+                        denom: coin.denom.clone(),
+                        amount: share,
+                    });
+                }
+                if !left.is_zero() {
+                    rest.push(Coin {
+                        denom: coin.denom,
+                        amount: left,
+                    });
+                }
+            }
+            Ok((rest.into(), cut.into()))
+        }
+        Balance::Cw20(coin) => {
+            let cut = apply_fraction(coin.amount, numerator, denominator, Rounding::Floor)?;
+            let rest = Cw20CoinVerified {
+                address: coin.address.clone(),
+                amount: coin.amount.checked_sub(cut)?,
+            };
+            let cut = Cw20CoinVerified {
+                address: coin.address,
+                amount: cut,
+            };
+            Ok((rest.into(), cut.into()))
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_start_auction(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    reserve: Coin,
+    min_increment_bps: u64,
+    end_height: u64,
+    buy_now: Option<Coin>,
+    private_to: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if reserve.amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "reserve must not be zero",
+        )));
+    }
+    if min_increment_bps > 10_000 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "min_increment_bps must not exceed 10000",
+        )));
+    }
+    // ensure the auction ends while the option can still be executed
+    if end_height <= _env.block.height || end_height >= state.expires {
+        return Err(ContractError::Std(StdError::generic_err(
+            "end_height must be after the current height and before the option expires",
+        )));
+    }
+    if let Some(buy_now) = &buy_now {
+        if buy_now.denom != reserve.denom || buy_now.amount < reserve.amount {
+            return Err(ContractError::Std(StdError::generic_err(
+                "buy_now must be in the reserve's denom and not below it",
+            )));
+        }
+    }
+    let private_to = validate_private_to(deps.as_ref(), private_to)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "start_auction");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("seller", info.sender.as_str());
+    res.add_attribute("reserve", reserve.to_string());
+    res.add_attribute("end_height", end_height.to_string());
+    // the contract holds the option until the auction is settled
+    hold_option(deps.storage, &mut res, id, &mut state, &_env)?;
+    AUCTIONS.save(
+        deps.storage,
+        id.into(),
+        &EnglishAuction {
+            seller: info.sender,
+            reserve,
+            min_increment_bps,
+            end_height,
+            high_bid: None,
+            buy_now,
+            private_to,
+        },
+    )?;
+    Ok(res)
+}
+
+pub fn try_bid_auction(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    ensure_not_expired(&state, &_env)?;
+    let mut auction = AUCTIONS
+        .may_load(deps.storage, id.into())?
+        .ok_or(ContractError::NoAuction {})?;
+    // ensure the auction still runs
+    if _env.block.height >= auction.end_height {
+        return Err(ContractError::AuctionEnded {});
+    }
+    // ensure msg.sender is not the seller
+    if info.sender == auction.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_private_buyer(&auction.private_to, &info.sender)?;
+    // ensure the bid is a single coin of the reserve's denom
+    let denom = auction.reserve.denom.clone();
+    let amount = match merge_denoms(info.funds)?.as_slice() {
+        [coin] if coin.denom == denom => coin.amount,
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "bid must be sent in {} only",
+                denom
+            ))))
+        }
+    };
+    // ensure the bid is nonzero, and outbids the high bid by the increment
+    let minimum = match &auction.high_bid {
+        None => Uint128::from(1u128),
+        Some(high_bid) => {
+            let increment = apply_fraction(
+                high_bid.amount.amount,
+                auction.min_increment_bps.into(),
+                10_000,
+                Rounding::Ceil,
+            )?
+            .max(Uint128::from(1u128));
+            high_bid
+                .amount
+                .amount
+                .checked_add(increment)
+                .map_err(StdError::from)?
+        }
+    };
+    if amount < minimum {
+        return Err(ContractError::BidTooLow {
+            minimum: Coin {
+                denom,
+                amount: minimum,
+            },
+        });
+    }
+    // ensure the bidder may hold the option
+    let bidder = validate_recipient(deps.as_ref(), &_env, info.sender.as_str())?;
+    ensure_allowed_owner(&state, &bidder)?;
+    ensure_not_blacklisted(deps.storage, &bidder)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "bid_auction");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("bidder", bidder.as_str());
+    res.add_attribute("amount", amount.to_string());
+    // refund the bid this one outbids
+    if let Some(outbid) = auction.high_bid.take() {
+        res.add_message(bank_send(&outbid.bidder, vec![outbid.amount]));
+    }
+    // keep late bids from sniping the auction, which still ends before the option expires
+    if auction.end_height - _env.block.height <= AUCTION_EXTENSION_BLOCKS {
+        let end_height = (auction.end_height + AUCTION_EXTENSION_BLOCKS).min(state.expires - 1);
+        if end_height > auction.end_height {
+            auction.end_height = end_height;
+            res.add_attribute("end_height", auction.end_height.to_string());
+        }
+    }
+    auction.high_bid = Some(AuctionBid {
+        bidder,
+        amount: Coin { denom, amount },
+    });
+    AUCTIONS.save(deps.storage, id.into(), &auction)?;
+    Ok(res)
+}
+
+pub fn try_settle_auction(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    let auction = AUCTIONS
+        .may_load(deps.storage, id.into())?
+        .ok_or(ContractError::NoAuction {})?;
+    // ensure the auction ended
+    if _env.block.height < auction.end_height {
+        return Err(ContractError::AuctionNotEnded {
+            end_height: auction.end_height,
+        });
+    }
+    AUCTIONS.remove(deps.storage, id.into());
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "settle_auction");
+    res.add_attribute("option_id", id.to_string());
+    let state = options().may_load(deps.storage, id.into())?;
+    match (state, auction.high_bid) {
+        // the high bidder buys the option, unless it expired before the auction was settled
+        (Some(mut state), Some(bid))
+            if bid.amount.amount >= auction.reserve.amount && _env.block.height < state.expires =>
+        {
+            res.add_attribute("winner", bid.bidder.as_str());
+            res.add_attribute("price", bid.amount.to_string());
+            pay_sale(
+                deps.storage,
+                &mut res,
+                &[&state],
+                &auction.seller,
+                vec![bid.amount].into(),
+            )?;
+            set_owner(deps.storage, &mut res, id, &mut state, bid.bidder)?;
+        }
+        // the reserve was not met, or the option expired or was burned meanwhile
+        (state, bid) => {
+            res.add_attribute("winner", "none");
+            if let Some(bid) = bid {
+                res.add_message(bank_send(&bid.bidder, vec![bid.amount]));
+            }
+            if let Some(mut state) = state {
+                set_owner(deps.storage, &mut res, id, &mut state, auction.seller)?;
+            }
+        }
+    }
+    Ok(res)
+}
+
+pub fn try_place_bid(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    valid_until: Expiration,
+) -> Result<Response, ContractError> {
+    let bid = merge_denoms(info.funds)?;
+    place_bid(deps, _env, info.sender, id, bid.into(), valid_until)
+}
+
+/// Escrows `bid` from `bidder`, in native coins or a cw20 token
+fn place_bid(
+    deps: DepsMut,
+    _env: Env,
+    bidder: Addr,
+    id: u64,
+    bid: Balance,
+    valid_until: Expiration,
+) -> Result<Response, ContractError> {
+    // get state
+    let state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    // ensure the bid can still be accepted
+    if valid_until.is_expired(&_env.block) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot place an expired bid",
+        )));
+    }
+    ensure_not_expired(&state, &_env)?;
+    // ensure the bidder is not already the owner
+    if bidder == state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure something is bid
+    if bid.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Must send funds to bid",
+        )));
+    }
+    // ensure a bid only replaces one in the same asset. Any contract can pose as a token
+    // sending on the bidder's behalf, and must not swap an escrowed bid for its own tokens
+    let previous = BIDS.may_load(deps.storage, (id.into(), &bidder))?;
+    if let Some(previous) = &previous {
+        if !same_asset(&previous.amount, &bid) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Bid must be in the asset of the escrowed bid, withdraw it first",
+            )));
+        }
+    }
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "place_bid");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("bidder", bidder.as_str());
+    res.add_attribute("bid", balance_to_string(&bid));
+
+    // refund the bid this one replaces
+    if let Some(previous) = previous {
+        res.add_message(send_balance(&bidder, previous.amount)?);
+    }
+    BIDS.save(
+        deps.storage,
+        (id.into(), &bidder),
+        &Bid {
+            amount: bid,
+            valid_until,
+        },
+    )?;
+    Ok(res)
+}
+
+pub fn try_withdraw_bid(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // the option may be gone already, the bid is refunded regardless
+    let bid = BIDS
+        .may_load(deps.storage, (id.into(), &info.sender))?
+        .ok_or(ContractError::NoBid {})?;
+    BIDS.remove(deps.storage, (id.into(), &info.sender));
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "withdraw_bid");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("bidder", info.sender.as_str());
+    res.add_message(send_balance(&info.sender, bid.amount)?);
+    Ok(res)
+}
+
+pub fn try_refund_expired_bids(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // no bid can be accepted once the option is settled
+    let settled = options().may_load(deps.storage, id.into())?.is_none();
+    let expired: Vec<(Vec<u8>, Bid)> = BIDS
+        .prefix(id.into())
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, bid)) => settled || bid.valid_until.is_expired(&_env.block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    // refunds go to the bidders, never the caller
+    let mut res: Response = Response::new();
+    for (key, bid) in expired.iter() {
+        let bidder = Addr::unchecked(parse_addr(key)?);
+        BIDS.remove(deps.storage, (id.into(), &bidder));
+        res.add_message(send_balance(&bidder, bid.amount.clone())?);
+    }
+
+    res.add_attribute("action", "refund_expired_bids");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("refunded", expired.len().to_string());
+    Ok(res)
+}
+
+pub fn try_accept_bid(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure the option may change hands
+    if !state.transferable {
+        return Err(ContractError::NonTransferable {});
+    }
+    ensure_unlocked(&state, &_env)?;
+    // ensure not paused
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the bidder may hold the option and the owner be paid
+    let bidder = validate_recipient(deps.as_ref(), &_env, &bidder)?;
+    let bid = BIDS
+        .may_load(deps.storage, (id.into(), &bidder))?
+        .ok_or(ContractError::NoBid {})?;
+    if bid.valid_until.is_expired(&_env.block) {
+        return Err(ContractError::ExpiredBid {});
+    }
+    ensure_not_expired(&state, &_env)?;
+    ensure_allowed_owner(&state, &bidder)?;
+    ensure_not_blacklisted(deps.storage, &bidder)?;
+    ensure_not_blacklisted(deps.storage, &state.owner)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "accept_bid");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("seller", state.owner.as_str());
+    res.add_attribute("buyer", bidder.as_str());
+    res.add_attribute("price", balance_to_string(&bid.amount));
+    pay_sale(deps.storage, &mut res, &[&state], &state.owner, bid.amount)?;
+
+    // settle every bid: the accepted one is paid out, the others refunded
+    let bids: Vec<(Vec<u8>, Bid)> = BIDS
+        .prefix(id.into())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for (key, other_bid) in bids {
+        let other = Addr::unchecked(parse_addr(&key)?);
+        BIDS.remove(deps.storage, (id.into(), &other));
+        if other != bidder {
+            res.add_message(send_balance(&other, other_bid.amount)?);
+        }
+    }
+
+    set_owner(deps.storage, &mut res, id, &mut state, bidder)?;
+    Ok(res)
+}
+
+pub fn try_claim_dust(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // ensure msg.sender is admin, or the creator of the first option if there is none
+    let settings = SETTINGS.load(deps.storage)?;
+    let authorized = match &settings.admin {
+        Some(admin) => *admin == info.sender,
+        None => matches!(
+            options().may_load(deps.storage, SINGLETON_ID.into())?,
+            Some(state) if state.creator == info.sender
+        ),
+    };
+    if !authorized {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // only what the contract holds beyond what it owes is dust. Only native coins are
+    // swept, so cw20 collateral and token bids stay untouched
+    let recorded = native_obligations(deps.storage, None)?;
+    let balance = deps.querier.query_all_balances(&_env.contract.address)?;
+    let dust = coins_beyond(balance, &recorded)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "claim_dust");
+    res.add_attribute("dust", coins_to_string(&dust));
+    if let Some(msg) = maybe_bank_send(&info.sender, dust) {
+        res.add_message(msg);
+    }
+    Ok(res)
+}
+
+pub fn try_update_blacklist(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // ensure msg.sender is admin
+    let settings = SETTINGS.load(deps.storage)?;
+    if settings.admin != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for address in add.iter() {
+        let address = deps.api.addr_validate(address)?;
+        BLACKLIST.save(deps.storage, &address, &Empty {})?;
+    }
+    for address in remove.iter() {
+        let address = deps.api.addr_validate(address)?;
+        BLACKLIST.remove(deps.storage, &address);
+    }
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "update_blacklist");
+    res.add_attribute("added", add.join(","));
+    res.add_attribute("removed", remove.join(","));
+    Ok(res)
+}
+
+pub fn try_set_market_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    market_fee_bps: u64,
+    treasury: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // ensure msg.sender is admin
+    let mut settings = SETTINGS.load(deps.storage)?;
+    if settings.admin != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if market_fee_bps > MAX_MARKET_FEE_BPS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "market_fee_bps must not exceed {}",
+            MAX_MARKET_FEE_BPS
+        ))));
+    }
+    // ensure a fee has somewhere to go
+    let treasury = treasury
+        .map(|treasury| deps.api.addr_validate(&treasury))
+        .transpose()?;
+    if market_fee_bps > 0 && treasury.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "treasury is required for a market fee",
+        )));
+    }
+
+    settings.market_fee_bps = market_fee_bps;
+    settings.treasury = treasury;
+    SETTINGS.save(deps.storage, &settings)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "set_market_fee");
+    res.add_attribute("market_fee_bps", market_fee_bps.to_string());
+    Ok(res)
+}
+
+pub fn try_set_memo(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // ensure the memo is within bounds
+    if memo.as_ref().map_or(0, String::len) > MAX_MEMO_LENGTH {
+        return Err(ContractError::MemoTooLong {});
+    }
+
+    state.memo = memo;
+    save_option(deps.storage, id, &state)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "set_memo");
+    res.add_attribute("option_id", id.to_string());
+    Ok(res)
+}
+
+pub fn try_set_reminder(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    blocks: u64,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    state.reminder_blocks = Some(blocks);
+    save_option(deps.storage, id, &state)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "set_reminder");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("blocks", blocks.to_string());
+    Ok(res)
+}
+
+pub fn try_set_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    // ensure sending no funds
+    nonpayable(&info)?;
+    // get state
+    let mut state: State = load_option(deps.storage, id)?;
+    // ensure msg.sender is creator
+    if info.sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    state.paused = paused;
+    save_option(deps.storage, id, &state)?;
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "set_paused");
+    res.add_attribute("option_id", id.to_string());
+    res.add_attribute("paused", paused.to_string());
+    Ok(res)
+}
+
+/// Returns `amount * numerator / denominator`, rounding any remainder in the given direction
+pub fn apply_fraction(
+    amount: Uint128,
+    numerator: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> StdResult<Uint128> {
+    let product = amount.checked_mul(Uint128::from(numerator))?;
+    let denominator = Uint128::from(denominator);
+    let quotient = product.checked_div(denominator)?;
+    match rounding {
+        Rounding::Floor => Ok(quotient),
+        Rounding::Ceil if product.checked_rem(denominator)?.is_zero() => Ok(quotient),
+        Rounding::Ceil => Ok(quotient.checked_add(Uint128::from(1u128))?),
+    }
+}
+
+#[entry_point]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::SettleExpired { limit } => sudo_settle_expired(deps, _env, limit),
+    }
+}
+
+/// Burns up to `limit` options whose grace period has passed, oldest expiry first,
+/// refunding their creators. Does nothing when no option is due
+pub fn sudo_settle_expired(
+    deps: DepsMut,
+    _env: Env,
+    limit: u64,
+) -> Result<Response, ContractError> {
+    let settings: Settings = SETTINGS.load(deps.storage)?;
+    let height = _env.block.height;
+    let due: Vec<(Vec<u8>, State)> = options()
+        .idx
+        .expires
+        .range(deps.storage, None, None, Order::Ascending)
+        .take_while(|item| match item {
+            Ok((_, state)) => state.expires.saturating_add(settings.grace_blocks) <= height,
+            Err(_) => true,
+        })
+        .take(limit as usize)
+        .collect::<StdResult<_>>()?;
+
+    let mut res: Response = Response::new();
+    let mut payouts: Vec<(Addr, Vec<Coin>)> = vec![];
+    for (key, state) in due.iter() {
+        let id = parse_id(key)?;
+        add_payout(&mut payouts, &state.creator, state.collateral.clone())?;
+        add_settle_hook(&mut res, id, state, SettleOutcome::Burned)?;
+        options().remove(deps.storage, id.into())?;
+        if let Some(extension) = EXTENSIONS.may_load(deps.storage, id.into())? {
+            EXTENSIONS.remove(deps.storage, id.into());
+            add_payout(&mut payouts, &extension.proposer, extension.incentive)?;
+        }
+    }
+
+    // release merged refunds, one send per recipient
+    for (recipient, amount) in payouts {
+        if let Some(msg) = maybe_bank_send(&recipient, amount) {
+            res.add_message(msg);
+        }
+    }
+    for (_, state) in due.iter() {
+        if let Some(msg) = maybe_cw20_send(&state.creator, state.cw20_collateral.clone())? {
+            res.add_message(msg);
+        }
+    }
+
+    res.add_attribute("action", "settle_expired");
+    res.add_attribute("settled", due.len().to_string());
+    Ok(res)
+}
+
+/// Only failed settle hooks reply, under the id of their option, reported without reverting
+/// the settlement. Any other reply is an error, so a new submessage cannot pass for a hook
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let next_id = NEXT_ID.load(deps.storage)?;
+    let err = match msg.result {
+        ContractResult::Err(err) if msg.id < next_id => err,
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "unexpected reply {}",
+                msg.id
+            ))))
+        }
+    };
+
+    let mut res: Response = Response::new();
+    res.add_attribute("action", "settle_hook_failed");
+    res.add_attribute("option_id", msg.id.to_string());
+    res.add_attribute("error", err);
+    Ok(res)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config { id } => to_binary(&query_config(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::Parties { id } => to_binary(&query_parties(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::OwnerOf { id } => {
+            to_binary(&query_owner_of(deps, _env, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::CreatorOf { id } => {
+            to_binary(&query_creator_of(deps, _env, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::CostBasis { id } => {
+            to_binary(&query_cost_basis(deps, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::ExecutePreview { id } => to_binary(&query_execute_preview(
+            deps,
+            _env,
+            id.unwrap_or(SINGLETON_ID),
+        )?),
+        QueryMsg::Export { id } => to_binary(&export_option(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::CanSettle { id } => {
+            to_binary(&query_can_settle(deps, _env, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::ExpiredAt { id, height } => {
+            to_binary(&query_expired_at(deps, id.unwrap_or(SINGLETON_ID), height)?)
+        }
+        QueryMsg::ThetaEstimate { id } => to_binary(&query_theta_estimate(
+            deps,
+            _env,
+            id.unwrap_or(SINGLETON_ID),
+        )?),
+        QueryMsg::Validate { id } => to_binary(&query_validate(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::PermitNonce { owner } => to_binary(&query_permit_nonce(deps, owner)?),
+        QueryMsg::CanExecute { id, address } => to_binary(&query_can_execute(
+            deps,
+            _env,
+            id.unwrap_or(SINGLETON_ID),
+            address,
+        )?),
+        QueryMsg::CreatorClaimable {
+            creator,
+            start_after,
+            limit,
+        } => to_binary(&query_creator_claimable(
+            deps,
+            _env,
+            creator,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Approvals { id } => {
+            to_binary(&query_approvals(deps, _env, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::Group { id } => to_binary(&query_group(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::GroupActionHash { id, action } => {
+            let id = id.unwrap_or(SINGLETON_ID);
+            let state = query_config(deps, id)?;
+            to_binary(&group_action_hash(id, &state, &action)?)
+        }
+        QueryMsg::Children {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_children(
+            deps,
+            id.unwrap_or(SINGLETON_ID),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PendingTransfer { id } => to_binary(&load_pending_transfer(
+            deps.storage,
+            &_env,
+            id.unwrap_or(SINGLETON_ID),
+        )?),
+        QueryMsg::Bids {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_bids(
+            deps,
+            _env,
+            id.unwrap_or(SINGLETON_ID),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::BidsByBidder {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_bids_by_bidder(
+            deps,
+            _env,
+            bidder,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Auction { id } => to_binary(&query_auction(deps, id.unwrap_or(SINGLETON_ID))?),
+        QueryMsg::Extension { id } => {
+            to_binary(&query_extension(deps, id.unwrap_or(SINGLETON_ID))?)
+        }
+        QueryMsg::LastExecution {} => to_binary(&query_last_execution(deps)?),
+        QueryMsg::Settings {} => to_binary(&query_settings(deps)?),
+        QueryMsg::Version {} => to_binary(&query_version(deps)?),
+        QueryMsg::Limits {} => to_binary(&query_limits(deps)?),
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::List { start_after, limit } => to_binary(&query_list(deps, start_after, limit)?),
+        QueryMsg::Listings {
+            seller,
+            start_after,
+            limit,
+        } => to_binary(&query_listings(deps, seller, start_after, limit)?),
+    }
+}
+
+fn query_config(deps: Deps, id: u64) -> StdResult<ConfigResponse> {
+    options()
+        .may_load(deps.storage, id.into())?
+        .ok_or_else(|| StdError::not_found(format!("option {}", id)))
+}
+
+fn query_execute_preview(deps: Deps, env: Env, id: u64) -> StdResult<ExecutePreviewResponse> {
+    let state = query_config(deps, id)?;
+    let height = env.block.height;
+    let executable = is_executable(&state, height);
+    let blocks_remaining = state.expires.saturating_sub(height);
+    let reminder_due = matches!(state.reminder_blocks, Some(blocks) if blocks_remaining <= blocks);
+    Ok(ExecutePreviewResponse {
+        pays: state.counter_offer,
+        receives: state.collateral,
+        executable,
+        blocks_remaining,
+        reminder_due,
+    })
+}
+
+fn export_option(deps: Deps, id: u64) -> StdResult<Binary> {
+    let state = query_config(deps, id)?;
+    to_binary(&ExportedOption { id, state })
+}
+
+/// Reads back an option serialized by QueryMsg::Export
+pub fn import_option(blob: Binary) -> StdResult<State> {
+    let exported: ExportedOption = from_binary(&blob)?;
+    Ok(exported.state)
+}
+
+fn query_can_settle(deps: Deps, env: Env, id: u64) -> StdResult<CanSettleResponse> {
+    let state = query_config(deps, id)?;
+    // the balance is shared, so only what is left after everything else owed backs the option
+    let owed = native_obligations(deps.storage, Some(id))?;
+    let balance = deps.querier.query_all_balances(&env.contract.address)?;
+    let available = coins_beyond(balance, &owed)?;
+    let mut can_settle = state.collateral.iter().all(|coin| {
+        available
+            .iter()
+            .any(|held| held.denom == coin.denom && held.amount >= coin.amount)
+    });
+    if let Some(collateral) = &state.cw20_collateral {
+        let owed = cw20_obligations(deps.storage, &collateral.address, id)?;
+        let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+            &collateral.address,
+            &Cw20QueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        can_settle &= balance.balance.saturating_sub(owed) >= collateral.amount;
+    }
+    Ok(CanSettleResponse { can_settle })
+}
+
+fn query_theta_estimate(deps: Deps, env: Env, id: u64) -> StdResult<ThetaEstimateResponse> {
+    let state = query_config(deps, id)?;
+    let remaining = state.expires.saturating_sub(env.block.height);
+    let total = state.expires.saturating_sub(state.created_at);
+    let fraction = if total == 0 {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(remaining.min(total), total)
+    };
+    Ok(ThetaEstimateResponse {
+        remaining,
+        total,
+        fraction,
+    })
+}
+
+fn query_expired_at(deps: Deps, id: u64, height: u64) -> StdResult<ExpiredAtResponse> {
+    let state = query_config(deps, id)?;
+    Ok(ExpiredAtResponse {
+        expired: height >= state.expires,
+    })
+}
+
+fn query_validate(deps: Deps, id: u64) -> StdResult<ValidateResponse> {
+    let state = query_config(deps, id)?;
+    // valid addresses also round-trip to the stored form
+    let is_valid =
+        |addr: &Addr| matches!(deps.api.addr_validate(addr.as_str()), Ok(a) if a == *addr);
+    Ok(ValidateResponse {
+        creator_valid: is_valid(&state.creator),
+        owner_valid: is_valid(&state.owner),
+    })
+}
+
+fn query_permit_nonce(deps: Deps, owner: String) -> StdResult<PermitNonceResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let nonce = PERMIT_NONCES
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+    Ok(PermitNonceResponse { nonce })
+}
+
+fn query_can_execute(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    address: String,
+) -> StdResult<CanExecuteResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let state = query_config(deps, id)?;
+    let executor = EXECUTORS
+        .may_load(deps.storage, (id.into(), &address))?
+        .is_some();
+    Ok(CanExecuteResponse {
+        can: (address == state.owner || executor) && is_executable(&state, env.block.height),
+    })
+}
+
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let mut total_options = 0;
+    let mut total_collateral = vec![];
+    let mut total_counter_offer = vec![];
+    for item in options().range(deps.storage, None, None, Order::Ascending) {
+        let (_, state) = item?;
+        total_options += 1;
+        add_coins(&mut total_collateral, state.collateral)?;
+        add_coins(&mut total_counter_offer, state.counter_offer)?;
+    }
+    Ok(StatsResponse {
+        total_options,
+        total_collateral: normalize_coins(total_collateral)?,
+        total_counter_offer: normalize_coins(total_counter_offer)?,
+    })
+}
+
+fn query_creator_claimable(
+    deps: Deps,
+    env: Env,
+    creator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<CreatorClaimableResponse> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+    let expired: Vec<(Vec<u8>, State)> = options()
+        .idx
+        .creator
+        .prefix(creator.as_str().as_bytes().to_vec())
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, state)) => env.block.height >= state.expires,
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    let mut ids = vec![];
+    let mut totals = vec![];
+    for (key, state) in expired {
+        ids.push(parse_id(&key)?);
+        add_coins(&mut totals, state.collateral)?;
+    }
+    Ok(CreatorClaimableResponse { ids, totals })
+}
+
+fn query_approvals(deps: Deps, env: Env, id: u64) -> StdResult<ApprovalsResponse> {
+    let approvals = APPROVALS
+        .prefix(id.into())
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .map(|item| {
+            let (key, expires) = item?;
+            Ok(Approval {
+                spender: Addr::unchecked(parse_addr(&key)?),
+                expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ApprovalsResponse { approvals })
+}
+
+fn query_group(deps: Deps, id: u64) -> StdResult<GroupResponse> {
+    query_config(deps, id)?;
+    GROUPS.may_load(deps.storage, id.into())
+}
+
+fn query_auction(deps: Deps, id: u64) -> StdResult<AuctionResponse> {
+    AUCTIONS.may_load(deps.storage, id.into())
+}
+
+fn query_extension(deps: Deps, id: u64) -> StdResult<ExtensionResponse> {
+    EXTENSIONS.may_load(deps.storage, id.into())
+}
+
+fn query_last_execution(deps: Deps) -> StdResult<LastExecutionResponse> {
+    LAST_EXECUTION.may_load(deps.storage)
+}
+
+fn query_settings(deps: Deps) -> StdResult<SettingsResponse> {
+    SETTINGS.load(deps.storage)
+}
+
+fn query_limits(deps: Deps) -> StdResult<LimitsResponse> {
+    let settings = SETTINGS.load(deps.storage)?;
+    Ok(LimitsResponse {
+        max_memo_length: MAX_MEMO_LENGTH as u64,
+        max_burn_batch: MAX_BURN_BATCH as u64,
+        max_keeper_bounty_bps: MAX_KEEPER_BOUNTY_BPS,
+        max_royalty_bps: MAX_ROYALTY_BPS,
+        max_market_fee_bps: MAX_MARKET_FEE_BPS,
+        max_page_limit: MAX_LIMIT,
+        default_page_limit: DEFAULT_LIMIT,
+        max_duration_blocks: settings.max_duration_blocks,
+        max_allowed_owners: MAX_ALLOWED_OWNERS as u64,
+    })
+}
+
+fn query_version(deps: Deps) -> StdResult<VersionResponse> {
+    get_contract_version(deps.storage)
+}
+
+fn query_parties(deps: Deps, id: u64) -> StdResult<PartiesResponse> {
+    let state = query_config(deps, id)?;
+    Ok(PartiesResponse {
+        is_transferred: state.owner != state.creator,
+        creator: state.creator,
+        owner: state.owner,
+    })
+}
+
+fn query_owner_of(deps: Deps, env: Env, id: u64) -> StdResult<OwnerOfResponse> {
+    let state = query_config(deps, id)?;
+    Ok(OwnerOfResponse {
+        status: option_status(&state, env.block.height),
+        owner: state.owner,
+    })
+}
+
+fn query_creator_of(deps: Deps, env: Env, id: u64) -> StdResult<CreatorOfResponse> {
+    let state = query_config(deps, id)?;
+    Ok(CreatorOfResponse {
+        status: option_status(&state, env.block.height),
+        creator: state.creator,
+    })
+}
+
+/// Status of the option at `height`, expiry taking precedence over a pause
+fn option_status(state: &State, height: u64) -> OptionStatus {
+    if height >= state.expires {
+        OptionStatus::Expired
+    } else if state.paused {
+        OptionStatus::Paused
+    } else {
+        OptionStatus::Active
+    }
+}
+
+fn query_cost_basis(deps: Deps, id: u64) -> StdResult<CostBasisResponse> {
+    let state = query_config(deps, id)?;
+    Ok(CostBasisResponse {
+        counter_offer: state.counter_offer,
+        collateral: state.collateral,
+    })
+}
+
+fn query_children(
+    deps: Deps,
+    id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ChildrenResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+    let ids = CHILDREN
+        .prefix(id.into())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| parse_id(&key))
+        .collect::<StdResult<_>>()?;
+    Ok(ChildrenResponse { ids })
+}
+
+fn query_list(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+    let options = options()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, state) = item?;
+            Ok((parse_id(&key)?, state))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ListResponse { options })
+}
+
+fn query_bids(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let bids = BIDS
+        .prefix(id.into())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, bid) = item?;
+            let bidder = Addr::unchecked(parse_addr(&key)?);
+            Ok(bid_info(&env, id, bidder, bid))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(BidsResponse { bids })
+}
+
+fn query_bids_by_bidder(
+    deps: Deps,
+    env: Env,
+    bidder: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let bidder = deps.api.addr_validate(&bidder)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // bids are keyed by option first, so every bid is scanned
+    let bids = BIDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| {
+            let (key, bid) = match item {
+                Ok(item) => item,
+                Err(e) => return Some(Err(e)),
+            };
+            match parse_bid_key(&key) {
+                Ok((id, addr)) if addr == bidder && start_after.map_or(true, |s| id > s) => {
+                    Some(Ok(bid_info(&env, id, bidder.clone(), bid)))
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(BidsResponse { bids })
+}
+
+fn bid_info(env: &Env, id: u64, bidder: Addr, bid: Bid) -> BidInfo {
+    BidInfo {
+        id,
+        bidder,
+        expired: bid.valid_until.is_expired(&env.block),
+        amount: bid.amount,
+        valid_until: bid.valid_until,
+    }
+}
+
+/// Splits a full BIDS key, the length-prefixed option id followed by the bidder
+fn parse_bid_key(key: &[u8]) -> StdResult<(u64, String)> {
+    match key {
+        [0, 8, rest @ ..] if rest.len() >= 8 => {
+            let (id, bidder) = rest.split_at(8);
+            Ok((parse_id(id)?, parse_addr(bidder)?))
+        }
+        _ => Err(StdError::generic_err("Corrupted bid key")),
+    }
+}
+
+fn query_listings(
+    deps: Deps,
+    seller: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let seller = seller.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+    // settled options are removed, so only live ones can show up
+    let listings = options()
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter_map(|item| {
+            let listing = match item.and_then(|(key, state)| listing(deps.storage, &key, state)) {
+                Ok(listing) => listing?,
+                Err(e) => return Some(Err(e)),
+            };
+            if matches!(&seller, Some(seller) if *seller != listing.seller) {
+                return None;
+            }
+            Some(Ok(listing))
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+/// The sale the option is up for, if any: a listing, an English auction or the creator's
+/// offer at the premium
+fn listing(storage: &dyn Storage, key: &[u8], state: State) -> StdResult<Option<Listing>> {
+    let id = parse_id(key)?;
+    let (seller, price, private_to) = match (state.ask, state.cw20_ask, state.auction) {
+        (Some(ask), _, _) => (state.owner, ListingPrice::Native(ask), state.private_to),
+        (_, Some(ask), _) => (state.owner, ListingPrice::Cw20(ask), state.private_to),
+        (_, _, Some(auction)) => (state.owner, ListingPrice::Dutch(auction), state.private_to),
+        _ => match (AUCTIONS.may_load(storage, id.into())?, state.premium) {
+            (Some(auction), _) => {
+                let private_to = auction.private_to.clone();
+                (
+                    auction.seller.clone(),
+                    ListingPrice::Auction(auction),
+                    private_to,
+                )
+            }
+            (_, Some(premium)) => (state.creator, ListingPrice::Primary(premium), None),
+            _ => return Ok(None),
+        },
+    };
+    Ok(Some(Listing {
+        id,
+        seller,
+        price,
+        expires: state.expires,
+        collateral: state.collateral,
+        private_to,
+    }))
+}
+
+fn parse_addr(key: &[u8]) -> StdResult<String> {
+    String::from_utf8(key.to_vec()).map_err(|_| StdError::generic_err("Corrupted address"))
+}
+
+fn parse_id(key: &[u8]) -> StdResult<u64> {
+    let bytes: [u8; 8] = key
+        .try_into()
+        .map_err(|_| StdError::generic_err("Corrupted option id"))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::PendingTransferResponse;
+    use crate::state::SettleHook;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        attr, coin, coins, Api, CanonicalAddr, OwnedDeps, RecoverPubkeyError, SubMsg,
+        SubcallResponse, VerificationError,
+    };
+    use cw20::Cw20Coin;
+    use cw_multi_test::{App, ContractWrapper, SimpleBank};
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use std::cell::RefCell;
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let _env = mock_env();
+        let info = mock_info("creator", &coins(1, "BTC"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(res.attributes, vec![attr("expires_height", "100000")]);
+
+        // it worked, let's query the state
+        let res: State = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(100_000, res.expires);
+        assert_eq!("creator", res.owner.as_str());
+        assert_eq!("creator", res.creator.as_str());
+        assert_eq!(coins(1, "BTC"), res.collateral);
+        assert_eq!(coins(40, "ETH"), res.counter_offer);
+        assert!(!res.paused);
+        assert_eq!(Rounding::Floor, res.rounding);
+
+        // and the version it was instantiated with
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Version {}).unwrap();
+        let version: VersionResponse = from_binary(&res).unwrap();
+        assert_eq!(CONTRACT_NAME, version.contract);
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn instantiate_with_owner() {
+        // by default the sender owns the option it writes
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("creator", state.creator);
+        assert_eq!("creator", state.owner);
+        assert!(!state.transferred);
+
+        // an invalid owner is rejected
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            owner: Some("x".into()),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+        // a given owner holds it from the start, the sender stays the creator
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            owner: Some("buyer".into()),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("creator", state.creator);
+        assert_eq!("buyer", state.owner);
+        assert!(state.transferred);
+
+        // so only the owner can execute, and the creator cannot reclaim
+        let info = mock_info("creator", &[]);
+        let _ = try_reclaim(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        let info = mock_info("buyer", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(SINGLETON_ID),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(40, "ETH"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: coins(1, "BTC"),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            max_duration_blocks: Some(200_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Limits {}).unwrap();
+        let limits: LimitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            LimitsResponse {
+                max_memo_length: MAX_MEMO_LENGTH as u64,
+                max_burn_batch: MAX_BURN_BATCH as u64,
+                max_keeper_bounty_bps: MAX_KEEPER_BOUNTY_BPS,
+                max_royalty_bps: MAX_ROYALTY_BPS,
+                max_market_fee_bps: MAX_MARKET_FEE_BPS,
+                max_page_limit: MAX_LIMIT,
+                default_page_limit: DEFAULT_LIMIT,
+                max_duration_blocks: Some(200_000),
+                max_allowed_owners: MAX_ALLOWED_OWNERS as u64,
+            },
+            limits
+        );
+    }
+
+    #[test]
+    fn unfunded_instantiation() {
+        let mut deps = mock_dependencies(&[]);
+
+        // rejected by default
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::NoCollateral {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a promise option when allowed
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            allow_unfunded: true,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.attributes.contains(&attr("unfunded", "true")));
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert!(state.collateral.is_empty());
+        assert_eq!("creator", state.owner.as_str());
+
+        // further options always need collateral
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[]);
+        let err = try_create(deps.as_mut(), mock_env(), info, create).unwrap_err();
+        match err {
+            ContractError::NoCollateral {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn max_collateral() {
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            max_collateral: Some(vec![coin(10, "BTC"), coin(500, "ATOM")]),
+            ..Default::default()
+        };
+
+        // within the cap, uncapped denoms pass
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &[coin(10, "BTC"), coin(1_000, "OSMO")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        // the cap holds for every further option
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(11, "BTC"));
+        let err = try_create(deps.as_mut(), mock_env(), info, create.clone()).unwrap_err();
+        match err {
+            ContractError::CollateralTooLarge { denom } => assert_eq!("BTC", denom),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &coins(10, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // above the cap
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &[coin(10, "BTC"), coin(501, "ATOM")]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::CollateralTooLarge { denom } => assert_eq!("ATOM", denom),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // no cap
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &coins(1_000_000, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn rounding() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            rounding: Some(Rounding::Ceil),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            Rounding::Ceil,
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().rounding
+        );
+
+        // a third of 10 rounds differently per mode
+        let amount = Uint128::from(10u128);
+        assert_eq!(
+            Uint128::from(3u128),
+            apply_fraction(amount, 1, 3, Rounding::Floor).unwrap()
+        );
+        assert_eq!(
+            Uint128::from(4u128),
+            apply_fraction(amount, 1, 3, Rounding::Ceil).unwrap()
+        );
+
+        // exact fractions are unaffected
+        assert_eq!(
+            Uint128::from(5u128),
+            apply_fraction(amount, 1, 2, Rounding::Floor).unwrap()
+        );
+        assert_eq!(
+            Uint128::from(5u128),
+            apply_fraction(amount, 1, 2, Rounding::Ceil).unwrap()
+        );
+
+        // zero denominator is an error rather than a panic
+        apply_fraction(amount, 1, 0, Rounding::Floor).unwrap_err();
+    }
+
+    #[test]
+    fn duplicate_denoms_merged() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: vec![coin(40, "ETH"), coin(3, "ATOM"), coin(10, "ETH")],
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(vec![coin(3, "ATOM"), coin(50, "ETH")], res.counter_offer);
+
+        // the merged counter_offer is what execute expects
+        let info = mock_info("creator", &[coin(50, "ETH"), coin(3, "ATOM")]);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.messages[0],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: vec![coin(3, "ATOM"), coin(50, "ETH")],
+            })
+        );
+    }
+
+    #[test]
     fn transfer() {
         let mut deps = mock_dependencies(&coins(2, "token"));
         // // we can just call .unwrap() to assert this was a success
         let msg = InstantiateMsg {
             counter_offer: coins(40, "ETH"),
             expires: 100_000,
+            ..Default::default()
+        };
+        let _env = mock_env();
+        let info = mock_info("creator", &coins(1, "BTC"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // random cannot transfer
+        let _env = mock_env();
+        let info = mock_info("anyone", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            _env,
+            info,
+            SINGLETON_ID,
+            "anyone".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // invalid recipient is rejected
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "x".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Std(StdError::GenericErr { msg, .. }) => {
+                assert!(msg.contains("too short"), "{}", msg)
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        let _env = mock_env();
+        // owner can transfer
+        let info = mock_info("creator", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            _env,
+            info,
+            SINGLETON_ID,
+            "someone".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "transfer"),
+                attr("option_id", "0"),
+                attr("sender", "creator"),
+                attr("previous_owner", "creator"),
+                attr("new_owner", "someone"),
+            ]
+        );
+        let res: State = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("someone", res.owner.as_str());
+        assert_eq!("creator", res.creator.as_str());
+    }
+
+    #[test]
+    fn transfer_memo() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // empty and oversized memos are rejected
+        for memo in ["".to_string(), "x".repeat(MAX_MEMO_LENGTH + 1)].iter() {
+            let info = mock_info("creator", &[]);
+            let err = try_transfer(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                SINGLETON_ID,
+                "someone".into(),
+                None,
+                Some(memo.clone()),
+            )
+            .unwrap_err();
+            match err {
+                ContractError::InvalidMemo { max } => assert_eq!(MAX_MEMO_LENGTH, max),
+                e => panic!("unexpected: {}", e),
+            }
+        }
+
+        // the memo is emitted and kept with the option
+        let info = mock_info("creator", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "someone".into(),
+            None,
+            Some("TICKET-42".into()),
+        )
+        .unwrap();
+        assert_eq!(attr("memo", "TICKET-42"), res.attributes[5]);
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(Some("TICKET-42".to_string()), state.last_transfer_memo);
+
+        // and replaced by the next transfer
+        let info = mock_info("someone", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "other".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(5, res.attributes.len());
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(None, state.last_transfer_memo);
+    }
+
+    #[test]
+    fn two_step_transfer() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the owner proposes, one proposal at a time
+        let info = mock_info("anyone", &[]);
+        let err = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let _ = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let err = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "other".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::TransferPending { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the wrong account cannot accept
+        let info = mock_info("other", &[]);
+        let err = try_accept_transfer(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // cancel drops the proposal
+        let info = mock_info("creator", &[]);
+        let res = try_cancel_transfer(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "cancel_transfer"), attr("option_id", "0")]
+        );
+        let info = mock_info("buyer", &[]);
+        let err = try_accept_transfer(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NoTransferPending { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // acceptance flips the owner
+        let info = mock_info("creator", &[]);
+        let _ = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let res = try_accept_transfer(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "accept_transfer"),
+                attr("option_id", "0"),
+                attr("from", "creator"),
+                attr("to", "buyer"),
+            ]
+        );
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("buyer", state.owner);
+        assert!(state.transferred);
+
+        // a direct transfer clears a pending proposal
+        let info = mock_info("buyer", &[]);
+        let _ = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "other".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("other", &[]);
+        let err = try_accept_transfer(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NoTransferPending { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_offer_expiration() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // cannot propose an already expired offer
+        let mut env = mock_env();
+        env.block.height = 20_000;
+        let info = mock_info("creator", &[]);
+        let _ = try_propose_transfer(
+            deps.as_mut(),
+            env,
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::AtHeight(20_000),
+        )
+        .unwrap_err();
+
+        for id in [SINGLETON_ID, 1].iter() {
+            let info = mock_info("creator", &[]);
+            let _ = try_propose_transfer(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                *id,
+                "buyer".into(),
+                Expiration::AtHeight(20_000),
+            )
+            .unwrap();
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PendingTransfer { id: Some(1) },
+        )
+        .unwrap();
+        let pending: PendingTransferResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            Some(PendingTransfer {
+                recipient: Addr::unchecked("buyer"),
+                valid_until: Expiration::AtHeight(20_000),
+            }),
+            pending
+        );
+
+        // accepted just before the deadline
+        let mut env = mock_env();
+        env.block.height = 19_999;
+        let info = mock_info("buyer", &[]);
+        let _ = try_accept_transfer(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            "buyer",
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().owner
+        );
+
+        // not once it passed
+        let mut env = mock_env();
+        env.block.height = 20_000;
+        let info = mock_info("buyer", &[]);
+        let err = try_accept_transfer(deps.as_mut(), env.clone(), info, 1).unwrap_err();
+        match err {
+            ContractError::NoTransferPending { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        assert_eq!(
+            None,
+            load_pending_transfer(deps.as_ref().storage, &env, 1).unwrap()
+        );
+
+        // the expired offer can be cleaned up by anyone
+        let info = mock_info("anyone", &[]);
+        let res = try_cleanup_proposals(deps.as_mut(), env, info, None).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "cleanup_proposals"), attr("removed", "1")]
+        );
+        assert_eq!(
+            None,
+            PENDING_TRANSFERS.may_load(&deps.storage, 1.into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn approvals() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the owner can approve
+        let info = mock_info("anyone", &[]);
+        let err = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "anyone".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _ = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "desk".into(),
+            Expiration::AtHeight(20_000),
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Approvals { id: None }).unwrap();
+        let approvals: ApprovalsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                Approval {
+                    spender: Addr::unchecked("broker"),
+                    expires: Expiration::Never {},
+                },
+                Approval {
+                    spender: Addr::unchecked("desk"),
+                    expires: Expiration::AtHeight(20_000),
+                },
+            ],
+            approvals.approvals
+        );
+
+        // an expired approval no longer allows a transfer
+        let mut env = mock_env();
+        env.block.height = 20_000;
+        let info = mock_info("desk", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            SINGLETON_ID,
+            "desk".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let res = query(deps.as_ref(), env, QueryMsg::Approvals { id: None }).unwrap();
+        let approvals: ApprovalsResponse = from_binary(&res).unwrap();
+        assert_eq!(1, approvals.approvals.len());
+
+        // the approved spender can transfer
+        let info = mock_info("broker", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(attr("sender", "broker"), res.attributes[2]);
+        assert_eq!(attr("previous_owner", "creator"), res.attributes[3]);
+        assert_eq!(
+            "buyer",
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().owner
+        );
+
+        // and loses the approval along with every other grant of the previous owner
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Approvals { id: None }).unwrap();
+        let approvals: ApprovalsResponse = from_binary(&res).unwrap();
+        assert!(approvals.approvals.is_empty());
+        let info = mock_info("broker", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // revoked approvals are dropped
+        let info = mock_info("buyer", &[]);
+        let _ = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let _ = try_revoke(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+        )
+        .unwrap();
+        assert!(!is_approved(
+            &deps.storage,
+            &mock_env(),
+            SINGLETON_ID,
+            &Addr::unchecked("broker")
+        )
+        .unwrap());
+
+        // approving an already expired grant is rejected
+        let info = mock_info("buyer", &[]);
+        let err = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            Expiration::AtHeight(12_345),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ExpiredApproval { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    fn receiver_accept(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ReceiveHookMsg,
+    ) -> StdResult<Response> {
+        let ReceiveHookMsg::ReceiveOption(msg) = msg;
+        let mut res = Response::new();
+        res.add_attribute("received", msg.id.to_string());
+        Ok(res)
+    }
+
+    fn receiver_reject(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: ReceiveHookMsg,
+    ) -> StdResult<Response> {
+        Err(StdError::generic_err("options not accepted"))
+    }
+
+    fn receiver_instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn receiver_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("no queries"))
+    }
+
+    #[test]
+    fn send_option() {
+        let mut app = App::new(
+            Box::new(MockApi::default()),
+            mock_env().block,
+            SimpleBank {},
+            || Box::new(MockStorage::new()),
+        );
+        let creator = Addr::unchecked("creator");
+        app.set_bank_balance(&creator, coins(1, "BTC")).unwrap();
+
+        let option_id = app.store_code(Box::new(ContractWrapper::new(
+            super::execute,
+            instantiate,
+            query,
+        )));
+        let accept_id = app.store_code(Box::new(ContractWrapper::new(
+            receiver_accept,
+            receiver_instantiate,
+            receiver_query,
+        )));
+        let reject_id = app.store_code(Box::new(ContractWrapper::new(
+            receiver_reject,
+            receiver_instantiate,
+            receiver_query,
+        )));
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let option = app
+            .instantiate_contract(option_id, creator.clone(), &msg, &coins(1, "BTC"), "option")
+            .unwrap();
+        let vault = app
+            .instantiate_contract(accept_id, creator.clone(), &Empty {}, &[], "vault")
+            .unwrap();
+        let escrow = app
+            .instantiate_contract(reject_id, creator.clone(), &Empty {}, &[], "escrow")
+            .unwrap();
+        let owner = |app: &App| {
+            let res: ConfigResponse = app
+                .wrap()
+                .query_wasm_smart(&option, &QueryMsg::Config { id: None })
+                .unwrap();
+            res.owner
+        };
+
+        // a rejecting receiver reverts the transfer
+        let send = ExecuteMsg::SendOption {
+            id: None,
+            contract: escrow.to_string(),
+            msg: Binary::from(b"deposit".as_ref()),
+        };
+        let err = app
+            .execute_contract(creator.clone(), option.clone(), &send, &[])
+            .unwrap_err();
+        assert!(err.contains("options not accepted"), "{}", err);
+        assert_eq!(creator, owner(&app));
+
+        // an accepting receiver becomes the owner
+        let send = ExecuteMsg::SendOption {
+            id: None,
+            contract: vault.to_string(),
+            msg: Binary::from(b"deposit".as_ref()),
+        };
+        let res = app
+            .execute_contract(creator.clone(), option.clone(), &send, &[])
+            .unwrap();
+        assert!(res.attributes.contains(&attr("received", "0")));
+        assert_eq!(vault, owner(&app));
+
+        // the receiver learns who sent which option, along with the payload
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let res = try_send_option(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "vault".into(),
+            Binary::from(b"deposit".as_ref()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault".into(),
+                msg: to_binary(&ReceiveHookMsg::ReceiveOption(ReceiveOptionMsg {
+                    sender: "creator".into(),
+                    id: SINGLETON_ID,
+                    msg: Binary::from(b"deposit".as_ref()),
+                }))
+                .unwrap(),
+                send: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn cw20_premiums() {
+        let mut app = App::new(
+            Box::new(MockApi::default()),
+            mock_env().block,
+            SimpleBank {},
+            || Box::new(MockStorage::new()),
+        );
+        let creator = Addr::unchecked("creator");
+        app.set_bank_balance(&creator, coins(1, "BTC")).unwrap();
+
+        let option_id = app.store_code(Box::new(ContractWrapper::new(
+            super::execute,
+            instantiate,
+            query,
+        )));
+        let token_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let mut tokens = vec![];
+        for symbol in ["USDX", "FAKE"] {
+            let msg = cw20_base::msg::InstantiateMsg {
+                name: "Stable".into(),
+                symbol: symbol.into(),
+                decimals: 6,
+                initial_balances: ["buyer", "alice", "bob"]
+                    .iter()
+                    .map(|holder| Cw20Coin {
+                        address: holder.to_string(),
+                        amount: Uint128::from(1_000u128),
+                    })
+                    .collect(),
+                mint: None,
+            };
+            let token = app
+                .instantiate_contract(token_id, creator.clone(), &msg, &[], symbol)
+                .unwrap();
+            tokens.push(token);
+        }
+        let (token, fake) = (tokens[0].clone(), tokens[1].clone());
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let option = app
+            .instantiate_contract(option_id, creator.clone(), &msg, &coins(1, "BTC"), "option")
+            .unwrap();
+
+        let pay = |app: &mut App, from: &str, token: &Addr, amount: u128, msg: Cw20HookMsg| {
+            let send = Cw20ExecuteMsg::Send {
+                contract: option.to_string(),
+                amount: Uint128::from(amount),
+                msg: Some(to_binary(&msg).unwrap()),
+            };
+            app.execute_contract(Addr::unchecked(from), token.clone(), &send, &[])
+        };
+        let balance = |app: &App, holder: &str| -> u128 {
+            let res: cw20::BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &token,
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: holder.into(),
+                    },
+                )
+                .unwrap();
+            res.balance.u128()
+        };
+        let owner = |app: &App| -> Addr {
+            let res: OwnerOfResponse = app
+                .wrap()
+                .query_wasm_smart(&option, &QueryMsg::OwnerOf { id: None })
+                .unwrap();
+            res.owner
+        };
+
+        let list = ExecuteMsg::ListCw20 {
+            id: None,
+            token: token.to_string(),
+            amount: Uint128::from(100u128),
+            private_to: None,
+        };
+        app.execute_contract(creator.clone(), option.clone(), &list, &[])
+            .unwrap();
+
+        // it can only be bought with the listed token, not another one or native coins
+        pay(&mut app, "buyer", &fake, 100, Cw20HookMsg::Buy { id: None }).unwrap_err();
+        app.execute_contract(
+            Addr::unchecked("buyer"),
+            option.clone(),
+            &ExecuteMsg::Buy { id: None },
+            &[],
+        )
+        .unwrap_err();
+        pay(
+            &mut app,
+            "buyer",
+            &token,
+            100,
+            Cw20HookMsg::Buy { id: None },
+        )
+        .unwrap();
+        assert_eq!(balance(&app, "creator"), 100);
+        assert_eq!(owner(&app), Addr::unchecked("buyer"));
+
+        // token bids are escrowed, paid out to the owner on acceptance and refunded otherwise
+        let bid = Cw20HookMsg::PlaceBid {
+            id: None,
+            valid_until: None,
+        };
+        pay(&mut app, "alice", &token, 50, bid.clone()).unwrap();
+        pay(&mut app, "bob", &token, 60, bid).unwrap();
+        assert_eq!(balance(&app, option.as_str()), 110);
+        let accept = ExecuteMsg::AcceptBid {
+            id: None,
+            bidder: "bob".into(),
+        };
+        app.execute_contract(Addr::unchecked("buyer"), option.clone(), &accept, &[])
+            .unwrap();
+        assert_eq!(balance(&app, "buyer"), 960);
+        assert_eq!(balance(&app, "alice"), 1_000);
+        assert_eq!(balance(&app, "bob"), 940);
+        assert_eq!(balance(&app, option.as_str()), 0);
+        assert_eq!(owner(&app), Addr::unchecked("bob"));
+    }
+
+    #[test]
+    fn cw20_collateral() {
+        let mut app = App::new(
+            Box::new(MockApi::default()),
+            mock_env().block,
+            SimpleBank {},
+            || Box::new(MockStorage::new()),
+        );
+        let creator = Addr::unchecked("creator");
+        app.set_bank_balance(&creator, coins(1, "BTC")).unwrap();
+        app.set_bank_balance(&Addr::unchecked("alice"), coins(40, "ETH"))
+            .unwrap();
+
+        let option_id = app.store_code(Box::new(ContractWrapper::new(
+            super::execute,
+            instantiate,
+            query,
+        )));
+        let token_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let msg = cw20_base::msg::InstantiateMsg {
+            name: "Stable".into(),
+            symbol: "USDX".into(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: creator.to_string(),
+                amount: Uint128::from(1_000u128),
+            }],
+            mint: None,
+        };
+        let token = app
+            .instantiate_contract(token_id, creator.clone(), &msg, &[], "USDX")
+            .unwrap();
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let option = app
+            .instantiate_contract(option_id, creator.clone(), &msg, &coins(1, "BTC"), "option")
+            .unwrap();
+
+        let create = |app: &mut App, amount: u128, owner: Option<String>| {
+            let msg = Cw20HookMsg::CreateOption(CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 100_000,
+                owner,
+                ..Default::default()
+            });
+            let send = Cw20ExecuteMsg::Send {
+                contract: option.to_string(),
+                amount: Uint128::from(amount),
+                msg: Some(to_binary(&msg).unwrap()),
+            };
+            app.execute_contract(creator.clone(), token.clone(), &send, &[])
+                .unwrap();
+        };
+        let balance = |app: &App, holder: &str| -> u128 {
+            let res: cw20::BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &token,
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: holder.into(),
+                    },
+                )
+                .unwrap();
+            res.balance.u128()
+        };
+
+        // the sent tokens back a new option, written for alice
+        create(&mut app, 500, Some("alice".into()));
+        let state: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(&option, &QueryMsg::Config { id: Some(1) })
+            .unwrap();
+        assert_eq!("creator", state.creator.as_str());
+        assert_eq!("alice", state.owner.as_str());
+        assert_eq!(Vec::<Coin>::new(), state.collateral);
+        assert_eq!(
+            Some(Cw20CoinVerified {
+                address: token.clone(),
+                amount: Uint128::from(500u128),
+            }),
+            state.cw20_collateral
+        );
+        assert_eq!(balance(&app, option.as_str()), 500);
+        let can_settle = |app: &App, id: u64| -> bool {
+            let res: CanSettleResponse = app
+                .wrap()
+                .query_wasm_smart(&option, &QueryMsg::CanSettle { id: Some(id) })
+                .unwrap();
+            res.can_settle
+        };
+        assert!(can_settle(&app, 1));
+
+        // executing pays the tokens out to the owner
+        let execute = ExecuteMsg::Execute(ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        });
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            option.clone(),
+            &execute,
+            &coins(40, "ETH"),
+        )
+        .unwrap();
+        assert_eq!(balance(&app, "alice"), 500);
+        assert_eq!(balance(&app, option.as_str()), 0);
+        let eth = app.wrap().query_balance("creator", "ETH").unwrap();
+        assert_eq!(eth, coin(40, "ETH"));
+
+        // the tokens cannot be split off, and burning refunds them to the creator
+        create(&mut app, 300, None);
+        let split = ExecuteMsg::TransferPartial {
+            id: Some(2),
+            recipient: "alice".into(),
+            collateral: vec![],
+        };
+        let err = app
+            .execute_contract(creator.clone(), option.clone(), &split, &[])
+            .unwrap_err();
+        assert!(err.contains("cw20 collateral"), "{}", err);
+        app.update_block(|block| block.height = 100_000);
+        let burn = ExecuteMsg::Burn { id: Some(2) };
+        app.execute_contract(creator.clone(), option.clone(), &burn, &[])
+            .unwrap();
+        assert_eq!(balance(&app, "creator"), 500);
+        assert_eq!(balance(&app, option.as_str()), 0);
+    }
+
+    #[test]
+    fn transfer_data() {
+        let mut app = App::new(
+            Box::new(MockApi::default()),
+            mock_env().block,
+            SimpleBank {},
+            || Box::new(MockStorage::new()),
+        );
+        let creator = Addr::unchecked("creator");
+        app.set_bank_balance(&creator, coins(1, "BTC")).unwrap();
+
+        let option_id = app.store_code(Box::new(ContractWrapper::new(
+            super::execute,
+            instantiate,
+            query,
+        )));
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let option = app
+            .instantiate_contract(option_id, creator.clone(), &msg, &coins(1, "BTC"), "option")
+            .unwrap();
+
+        // the caller can tell which option moved and from whom
+        let transfer = ExecuteMsg::Transfer {
+            id: None,
+            recipient: "market".into(),
+            execution_id: None,
+            memo: None,
+        };
+        let res = app
+            .execute_contract(creator, option, &transfer, &[])
+            .unwrap();
+        let data: TransferResponse = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            TransferResponse {
+                id: SINGLETON_ID,
+                previous_owner: Addr::unchecked("creator"),
+                new_owner: Addr::unchecked("market"),
+            }
+        );
+        assert!(res.attributes.contains(&attr("action", "transfer")));
+    }
+
+    /// MockApi that humanizes 20 byte account hashes the way a chain would, so addresses
+    /// can be derived from public keys. The prefix keeps them short enough to validate
+    #[derive(Copy, Clone, Default)]
+    struct PermitApi(MockApi);
+
+    impl Api for PermitApi {
+        fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+            self.0.addr_validate(human)
+        }
+        fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+            self.0.addr_canonicalize(human)
+        }
+        fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+            if canonical.len() != 20 {
+                return self.0.addr_humanize(canonical);
+            }
+            let hex: String = canonical.as_slice()[..4]
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            Ok(Addr::unchecked(format!("acct{}", hex)))
+        }
+        fn secp256k1_verify(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.secp256k1_verify(message_hash, signature, public_key)
+        }
+        fn secp256k1_recover_pubkey(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            recovery_param: u8,
+        ) -> Result<Vec<u8>, RecoverPubkeyError> {
+            self.0
+                .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+        }
+        fn ed25519_verify(
+            &self,
+            message: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.ed25519_verify(message, signature, public_key)
+        }
+        fn ed25519_batch_verify(
+            &self,
+            messages: &[&[u8]],
+            signatures: &[&[u8]],
+            public_keys: &[&[u8]],
+        ) -> Result<bool, VerificationError> {
+            self.0
+                .ed25519_batch_verify(messages, signatures, public_keys)
+        }
+        fn debug(&self, message: &str) {
+            self.0.debug(message)
+        }
+    }
+
+    /// MockApi that records debug messages instead of printing them
+    #[derive(Default)]
+    struct DebugApi {
+        api: MockApi,
+        messages: RefCell<Vec<String>>,
+    }
+
+    impl Api for DebugApi {
+        fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+            self.api.addr_validate(human)
+        }
+        fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+            self.api.addr_canonicalize(human)
+        }
+        fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+            self.api.addr_humanize(canonical)
+        }
+        fn secp256k1_verify(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.api
+                .secp256k1_verify(message_hash, signature, public_key)
+        }
+        fn secp256k1_recover_pubkey(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            recovery_param: u8,
+        ) -> Result<Vec<u8>, RecoverPubkeyError> {
+            self.api
+                .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+        }
+        fn ed25519_verify(
+            &self,
+            message: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.api.ed25519_verify(message, signature, public_key)
+        }
+        fn ed25519_batch_verify(
+            &self,
+            messages: &[&[u8]],
+            signatures: &[&[u8]],
+            public_keys: &[&[u8]],
+        ) -> Result<bool, VerificationError> {
+            self.api
+                .ed25519_batch_verify(messages, signatures, public_keys)
+        }
+        fn debug(&self, message: &str) {
+            self.messages.borrow_mut().push(message.to_string())
+        }
+    }
+
+    #[test]
+    fn unauthorized_logged() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: DebugApi::default(),
+            querier: MockQuerier::<Empty>::new(&[]),
+        };
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a stranger trying to transfer or execute the option is logged
+        let transfer = ExecuteMsg::Transfer {
+            id: None,
+            recipient: "thief".into(),
+            execution_id: None,
+            memo: None,
+        };
+        let execute = ExecuteMsg::Execute(ExecuteOptionMsg::default());
+        for (msg, funds) in [(transfer, vec![]), (execute, coins(40, "ETH"))] {
+            let info = mock_info("stranger", &funds);
+            let err = super::execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            match err {
+                ContractError::Unauthorized {} => {}
+                e => panic!("unexpected: {}", e),
+            }
+        }
+        assert_eq!(
+            *deps.api.messages.borrow(),
+            vec!["unauthorized_sender: stranger"; 2]
+        );
+
+        // other errors are not
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Burn { id: None };
+        let _ = super::execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(deps.api.messages.borrow().len(), 2);
+    }
+
+    /// Signs a permit for transferring option `id` to `recipient`
+    fn sign_permit(
+        key: &SigningKey,
+        id: u64,
+        recipient: &str,
+        nonce: u64,
+        expires: Expiration,
+    ) -> Permit {
+        let env = mock_env();
+        let msg = PermitMsg {
+            contract: env.contract.address.to_string(),
+            chain_id: env.block.chain_id,
+            id,
+            recipient: recipient.to_string(),
+            nonce,
+            expires,
+        };
+        let signature: Signature = key.sign(&to_vec(&msg).unwrap());
+        Permit {
+            pubkey: Binary::from(key.verify_key().to_bytes().as_slice()),
+            nonce,
+            expires,
+            signature: Binary::from(signature.as_ref()),
+        }
+    }
+
+    #[test]
+    fn transfer_with_permit() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: PermitApi::default(),
+            querier: MockQuerier::<Empty>::new(&[]),
+        };
+        let key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let other = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+        let expires = Expiration::AtHeight(20_000);
+
+        // the permit's signer is derived from its key
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let permit = sign_permit(&key, SINGLETON_ID, "buyer", 0, expires);
+        let signer =
+            permit_signer(deps.as_ref(), &mock_env(), SINGLETON_ID, "buyer", &permit).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            signer.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // a key other than the owner's is refused
+        let info = mock_info("relayer", &[]);
+        let forged = sign_permit(&other, SINGLETON_ID, "buyer", 0, expires);
+        let err = try_transfer_with_permit(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            forged,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // so is a permit for another recipient
+        let info = mock_info("relayer", &[]);
+        let err = try_transfer_with_permit(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "thief".into(),
+            permit.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InvalidPermit { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // and an expired one
+        let mut env = mock_env();
+        env.block.height = 20_000;
+        let info = mock_info("relayer", &[]);
+        let err = try_transfer_with_permit(
+            deps.as_mut(),
+            env,
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            permit.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ExpiredPermit { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the relayer submits the owner's permit
+        let info = mock_info("relayer", &[]);
+        let res = try_transfer_with_permit(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            permit.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[0], attr("action", "transfer_with_permit"));
+        let res = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("buyer", res.owner.as_str());
+        let res = query_permit_nonce(deps.as_ref(), signer.to_string()).unwrap();
+        assert_eq!(1, res.nonce);
+
+        // once the option is back with the signer, the used permit cannot be replayed
+        let info = mock_info("buyer", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            signer.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("relayer", &[]);
+        let err = try_transfer_with_permit(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            permit,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InvalidPermit { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a permit with the next nonce goes through
+        let permit = sign_permit(&key, SINGLETON_ID, "buyer", 1, expires);
+        let info = mock_info("relayer", &[]);
+        let _ = try_transfer_with_permit(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            permit,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn non_transferable() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            transferable: Some(false),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(
+            !query_config(deps.as_ref(), SINGLETON_ID)
+                .unwrap()
+                .transferable
+        );
+
+        // no way to hand it on
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NonTransferable {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NonTransferable {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_send_option(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "vault".into(),
+            Binary::from(b"{}".as_ref()),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NonTransferable {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NonTransferable {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // but it exercises as usual
+        let info = mock_info("creator", &counter_offer);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(2, res.messages.len());
+
+        // options are transferable unless asked otherwise
+        let create = CreateMsg {
+            counter_offer,
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        assert!(query_config(deps.as_ref(), 1).unwrap().transferable);
+    }
+
+    #[test]
+    fn transfer_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+
+        // the lock cannot outlast the option
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            transfer_lock_until: Some(100_001),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            transfer_lock_until: Some(20_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            Some(20_000),
+            query_config(deps.as_ref(), SINGLETON_ID)
+                .unwrap()
+                .transfer_lock_until
+        );
+
+        // approvals can be granted during the lock, but not used
+        let info = mock_info("creator", &[]);
+        let _ = try_approve(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "broker".into(),
+            Expiration::Never {},
+        )
+        .unwrap();
+        let mut env = mock_env();
+        env.block.height = 19_999;
+        for sender in ["creator", "broker"].iter() {
+            let info = mock_info(sender, &[]);
+            let err = try_transfer(
+                deps.as_mut(),
+                env.clone(),
+                info,
+                SINGLETON_ID,
+                "buyer".into(),
+                None,
+                None,
+            )
+            .unwrap_err();
+            match err {
+                ContractError::TransferLocked { until } => assert_eq!(20_000, until),
+                e => panic!("unexpected: {}", e),
+            }
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_propose_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::TransferLocked { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the approved spender can transfer once the lock ends
+        env.block.height = 20_000;
+        let info = mock_info("broker", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // exercising is never locked
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            transfer_lock_until: Some(20_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        let info = mock_info("creator", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn transfer_partial() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(4, "BTC"), coin(100, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // cannot carve out all, or more than, the collateral
+        let info = mock_info("creator", &[]);
+        let err = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            vec![coin(4, "BTC"), coin(100, "ATOM")],
+        )
+        .unwrap_err();
+        match err {
+            ContractError::PartialTooLarge {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // nor uneven shares of the denoms
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            vec![coin(1, "BTC"), coin(50, "ATOM")],
+        )
+        .unwrap_err();
+
+        // only the owner can
+        let info = mock_info("buyer", &[]);
+        let err = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            vec![coin(1, "BTC"), coin(25, "ATOM")],
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a quarter moves into a new option
+        let info = mock_info("creator", &[]);
+        let res = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            vec![coin(25, "ATOM"), coin(1, "BTC")],
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "transfer_partial"),
+                attr("option_id", "0"),
+                attr("child_id", "1"),
+                attr("new_owner", "buyer"),
+                attr("collateral", "25ATOM,1BTC"),
+            ]
+        );
+        let parent = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("creator", parent.owner);
+        assert_eq!(vec![coin(75, "ATOM"), coin(3, "BTC")], parent.collateral);
+        assert_eq!(coins(30, "ETH"), parent.counter_offer);
+        let child = query_config(deps.as_ref(), 1).unwrap();
+        assert_eq!("buyer", child.owner);
+        assert_eq!("creator", child.creator);
+        assert_eq!(vec![coin(25, "ATOM"), coin(1, "BTC")], child.collateral);
+        assert_eq!(coins(10, "ETH"), child.counter_offer);
+        assert_eq!(parent.expires, child.expires);
+        assert!(child.transferred);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Children {
+                id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let children: ChildrenResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![1], children.ids);
+        assert!(query_children(deps.as_ref(), 1, None, None)
+            .unwrap()
+            .ids
+            .is_empty());
+
+        // the new option exercises on its own
+        let info = mock_info("buyer", &coins(10, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[1],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "buyer".into(),
+                amount: vec![coin(25, "ATOM"), coin(1, "BTC")],
+            })
+        );
+    }
+
+    #[test]
+    fn allowed_owners() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            allowed_owners: Some(vec!["fund".into(), "fund".into()]),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            Some(vec![Addr::unchecked("fund")]),
+            query_config(deps.as_ref(), SINGLETON_ID)
+                .unwrap()
+                .allowed_owners
+        );
+
+        // unlisted recipients are rejected
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "desk".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::RecipientNotAllowed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // listed ones are fine, and the creator can always take it back
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "fund".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("fund", &[]);
+        let err = try_send_option(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "desk".into(),
+            Binary::from(b"{}".as_ref()),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::RecipientNotAllowed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("fund", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "creator".into(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // only the creator can extend the list
+        let info = mock_info("fund", &[]);
+        let err = try_allow_owners(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec!["desk".into()],
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let res = try_allow_owners(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec!["desk".into()],
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "allow_owners"),
+                attr("option_id", "0"),
+                attr("added", "1"),
+            ]
+        );
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "desk".into(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // the list stays bounded
+        let info = mock_info("creator", &[]);
+        let addresses = (0..MAX_ALLOWED_OWNERS)
+            .map(|i| format!("holder{}", i))
+            .collect();
+        let _ =
+            try_allow_owners(deps.as_mut(), mock_env(), info, SINGLETON_ID, addresses).unwrap_err();
+
+        // an unrestricted option cannot be turned into a restricted one
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ =
+            try_allow_owners(deps.as_mut(), mock_env(), info, 1, vec!["desk".into()]).unwrap_err();
+    }
+
+    #[test]
+    fn blacklist() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            admin: Some("admin".into()),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the admin maintains the list
+        let info = mock_info("creator", &[]);
+        let err = try_update_blacklist(deps.as_mut(), mock_env(), info, vec!["bad".into()], vec![])
+            .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("admin", &[]);
+        let res = try_update_blacklist(deps.as_mut(), mock_env(), info, vec!["bad".into()], vec![])
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "update_blacklist"),
+                attr("added", "bad"),
+                attr("removed", ""),
+            ]
+        );
+
+        // a blacklisted address cannot become owner
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "bad".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Blacklisted { address } => assert_eq!("bad", address),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // nor receive the collateral
+        let info = mock_info("creator", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            collateral_recipient: Some(Addr::unchecked("bad")),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+        // an owner blacklisted later cannot execute
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "holder".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("admin", &[]);
+        let _ = try_update_blacklist(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec!["holder".into()],
+            vec![],
+        )
+        .unwrap();
+        let info = mock_info("holder", &counter_offer);
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::Blacklisted { address } => assert_eq!("holder", address),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // but the collateral is not stranded, it is burned back to the creator at expiry
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+
+        // removed entries can own again
+        let info = mock_info("admin", &[]);
+        let _ = try_update_blacklist(deps.as_mut(), mock_env(), info, vec![], vec!["bad".into()])
+            .unwrap();
+        let create = CreateMsg {
+            counter_offer,
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(deps.as_mut(), mock_env(), info, 1, "bad".into(), None, None).unwrap();
+    }
+
+    #[test]
+    fn transfer_to_contract() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let env = mock_env();
+        let contract = env.contract.address.to_string();
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            env,
+            info,
+            SINGLETON_ID,
+            contract.clone(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::CannotTransferToSelf { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_propose_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            contract.clone(),
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::CannotTransferToSelf { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_send_option(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            contract,
+            Binary::from(b"{}".as_ref()),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::CannotTransferToSelf { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        assert_eq!(
+            "creator",
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().owner
+        );
+    }
+
+    #[test]
+    fn execute_lenient() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = vec![coin(40, "ETH"), coin(10, "ATOM")];
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // underpayment in any denom is rejected
+        let info = mock_info("owner", &[coin(50, "ETH"), coin(9, "ATOM")]);
+        let err = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Std(StdError::GenericErr { msg, .. }) => {
+                assert!(msg.starts_with("must send at least counter_offer"))
+            }
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("owner", &coins(40, "ETH"));
+        let _ = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+
+        // exact payment produces no refund
+        let info = mock_info("owner", &counter_offer);
+        let res = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(res.messages.len(), 2);
+
+        // surplus, including an unrequested denom, goes back to the sender
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info(
+            "owner",
+            &[coin(45, "ETH"), coin(10, "ATOM"), coin(3, "OSMO")],
+        );
+        let res = try_execute_lenient(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: vec![coin(10, "ATOM"), coin(40, "ETH")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "owner".into(),
+                    amount: collateral,
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "owner".into(),
+                    amount: vec![coin(5, "ETH"), coin(3, "OSMO")],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn execute_validation_order() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // expired and mis-funded
+        let mut env = mock_env();
+        env.block.height = 200_000;
+
+        // by default expiry is reported first
+        let info = mock_info("creator", &coins(39, "ETH"));
+        let err = try_execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteOptionMsg::default(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Expired { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // strict_order reports the funds first
+        let info = mock_info("creator", &coins(39, "ETH"));
+        let err = try_execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteOptionMsg {
+                strict_order: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::CounterOfferMismatch { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn duplicate_execution() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "creator".to_string(),
+            Some("tx-1".to_string()),
+            None,
+        )
+        .unwrap();
+
+        // same id is rejected the second time
+        let info = mock_info("creator", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "creator".to_string(),
+            Some("tx-1".to_string()),
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DuplicateExecution { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // ids are shared between transfer and execute
+        let info = mock_info("creator", &counter_offer);
+        let err = try_execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteOptionMsg {
+                execution_id: Some("tx-1".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DuplicateExecution { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a fresh id goes through
+        let info = mock_info("creator", &counter_offer);
+        let res = try_execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteOptionMsg {
+                execution_id: Some("tx-2".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+    }
+
+    #[test]
+    fn cost_basis() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(1, "BTC"), coin(5, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        let res = query_cost_basis(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(
+            res,
+            CostBasisResponse {
+                counter_offer: state.counter_offer,
+                collateral: state.collateral,
+            }
+        );
+        assert_eq!(coins(40, "ETH"), res.counter_offer);
+        assert_eq!(vec![coin(5, "ATOM"), coin(1, "BTC")], res.collateral);
+    }
+
+    #[test]
+    fn execute_preview() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height = 99_999;
+        let res = query(deps.as_ref(), env, QueryMsg::ExecutePreview { id: None }).unwrap();
+        let preview: ExecutePreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ExecutePreviewResponse {
+                pays: coins(40, "ETH"),
+                receives: coins(1, "BTC"),
+                executable: true,
+                blocks_remaining: 1,
+                reminder_due: false,
+            },
+            preview
+        );
+
+        // expired
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let preview = query_execute_preview(deps.as_ref(), env, SINGLETON_ID).unwrap();
+        assert!(!preview.executable);
+        assert_eq!(0, preview.blocks_remaining);
+        assert_eq!(coins(40, "ETH"), preview.pays);
+        assert_eq!(coins(1, "BTC"), preview.receives);
+    }
+
+    #[test]
+    fn reminder() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the owner sets it
+        let info = mock_info("anyone", &[]);
+        let _ = try_set_reminder(deps.as_mut(), mock_env(), info, SINGLETON_ID, 100).unwrap_err();
+        let info = mock_info("creator", &[]);
+        let _ = try_set_reminder(deps.as_mut(), mock_env(), info, SINGLETON_ID, 100).unwrap();
+
+        let preview_at = |deps: &OwnedDeps<_, _, _>, height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            query_execute_preview(deps.as_ref(), env, SINGLETON_ID).unwrap()
+        };
+        assert!(!preview_at(&deps, 99_500).reminder_due);
+        assert!(preview_at(&deps, 99_900).reminder_due);
+        assert!(preview_at(&deps, 99_950).reminder_due);
+
+        // a new owner starts without one
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(!preview_at(&deps, 99_950).reminder_due);
+    }
+
+    #[test]
+    fn can_execute() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the owner can
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CanExecute {
+                id: None,
+                address: "creator".into(),
+            },
+        )
+        .unwrap();
+        let res: CanExecuteResponse = from_binary(&res).unwrap();
+        assert!(res.can);
+
+        // a stranger cannot
+        let res =
+            query_can_execute(deps.as_ref(), mock_env(), SINGLETON_ID, "anyone".into()).unwrap();
+        assert!(!res.can);
+
+        // nor can the owner once expired
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let res = query_can_execute(deps.as_ref(), env, SINGLETON_ID, "creator".into()).unwrap();
+        assert!(!res.can);
+    }
+
+    #[test]
+    fn executors() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // only the owner adds executors
+        let info = mock_info("relayer1", &[]);
+        let err =
+            try_add_executor(deps.as_mut(), mock_env(), info, 1, "relayer1".into()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        for id in [SINGLETON_ID, 1] {
+            for relayer in ["relayer1", "relayer2"] {
+                let info = mock_info("creator", &[]);
+                let _ =
+                    try_add_executor(deps.as_mut(), mock_env(), info, id, relayer.into()).unwrap();
+            }
+        }
+        let res =
+            query_can_execute(deps.as_ref(), mock_env(), SINGLETON_ID, "relayer2".into()).unwrap();
+        assert!(res.can);
+
+        // an executor executes on the owner's behalf, the owner being paid the collateral
+        let info = mock_info("relayer1", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(SINGLETON_ID),
+            collateral_recipient: Some(Addr::unchecked("relayer1")),
+            ..Default::default()
+        };
+        let err = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("relayer1", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(SINGLETON_ID),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(40, "ETH"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(1, "BTC"),
+                }),
+            ]
+        );
+
+        // until removed
+        let info = mock_info("creator", &[]);
+        let _ = try_remove_executor(deps.as_mut(), mock_env(), info, 1, "relayer1".into()).unwrap();
+        let info = mock_info("relayer1", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let err = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = query_can_execute(deps.as_ref(), mock_env(), 1, "relayer1".into()).unwrap();
+        assert!(!res.can);
+
+        // and the others are dropped with a change of owner
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "owner".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("relayer2", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let err = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn stats() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for (counter_offer, collateral) in [
+            (vec![coin(10, "ETH"), coin(5, "ATOM")], coins(2, "BTC")),
+            (coins(7, "OSMO"), vec![coin(100, "ATOM"), coin(3, "BTC")]),
+        ] {
+            let create = CreateMsg {
+                counter_offer,
+                expires: 100_000,
+                ..Default::default()
+            };
+            let info = mock_info("other", &collateral);
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let stats: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            stats,
+            StatsResponse {
+                total_options: 3,
+                total_collateral: vec![coin(100, "ATOM"), coin(6, "BTC")],
+                total_counter_offer: vec![coin(5, "ATOM"), coin(50, "ETH"), coin(7, "OSMO")],
+            }
+        );
+    }
+
+    #[test]
+    fn creator_claimable() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 1_000_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // ids 1 to 4, expiring at 20_000, 30_000, 40_000 and 50_000
+        for (i, sender) in ["creator", "creator", "other", "creator"]
+            .iter()
+            .enumerate()
+        {
+            let create = CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 20_000 + 10_000 * i as u64,
+                ..Default::default()
+            };
+            let info = mock_info(sender, &[coin(100, "ATOM"), coin(7, "OSMO")]);
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+
+        let mut env = mock_env();
+        env.block.height = 45_000;
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::CreatorClaimable {
+                creator: "creator".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let claimable: CreatorClaimableResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            CreatorClaimableResponse {
+                ids: vec![1, 2],
+                totals: vec![coin(200, "ATOM"), coin(14, "OSMO")],
+            },
+            claimable
+        );
+
+        // paginated
+        let claimable =
+            query_creator_claimable(deps.as_ref(), env.clone(), "creator".into(), None, Some(1))
+                .unwrap();
+        assert_eq!(vec![1], claimable.ids);
+        let claimable =
+            query_creator_claimable(deps.as_ref(), env, "creator".into(), Some(1), Some(1))
+                .unwrap();
+        assert_eq!(vec![2], claimable.ids);
+        assert_eq!(vec![coin(100, "ATOM"), coin(7, "OSMO")], claimable.totals);
+
+        // settled options drop out
+        let mut env = mock_env();
+        env.block.height = 45_000;
+        let info = mock_info("creator", &[]);
+        let _ = try_burn(deps.as_mut(), env.clone(), info, 1).unwrap();
+        let claimable =
+            query_creator_claimable(deps.as_ref(), env, "creator".into(), None, None).unwrap();
+        assert_eq!(vec![2], claimable.ids);
+    }
+
+    #[test]
+    fn multiple_options() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // anyone can write further options
+        let create = CreateMsg {
+            counter_offer: coins(10, "ETH"),
+            expires: 200_000,
+            ..Default::default()
+        };
+        let info = mock_info("writer", &coins(2, "BTC"));
+        let res = try_create(deps.as_mut(), mock_env(), info, create.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "create"), attr("option_id", "1")]
+        );
+        let info = mock_info("writer", &coins(3, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // expired options cannot be created
+        let create = CreateMsg {
+            counter_offer: coins(10, "ETH"),
+            expires: mock_env().block.height,
+            ..Default::default()
+        };
+        let info = mock_info("writer", &coins(2, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap_err();
+
+        // handlers act on the given id only
+        let info = mock_info("writer", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "buyer".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let option = query_config(deps.as_ref(), 1).unwrap();
+        assert_eq!("buyer", option.owner.as_str());
+        assert_eq!("writer", option.creator.as_str());
+        assert_eq!(coins(2, "BTC"), option.collateral);
+        let option = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("creator", option.owner.as_str());
+
+        // a missing id defaults to the first option
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config { id: None }).unwrap();
+        let option: State = from_binary(&res).unwrap();
+        assert_eq!("creator", option.creator.as_str());
+
+        // paginated listing
+        let res = query_list(deps.as_ref(), None, Some(2)).unwrap();
+        let ids: Vec<u64> = res.options.iter().map(|(id, _)| *id).collect();
+        assert_eq!(vec![0, 1], ids);
+        assert_eq!("buyer", res.options[1].1.owner.as_str());
+        let res = query_list(deps.as_ref(), Some(1), Some(2)).unwrap();
+        let ids: Vec<u64> = res.options.iter().map(|(id, _)| *id).collect();
+        assert_eq!(vec![2], ids);
+        let res = query_list(deps.as_ref(), Some(2), None).unwrap();
+        assert!(res.options.is_empty());
+
+        // events name the option acted on
+        let info = mock_info("buyer", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "holder".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(attr("option_id", "1"), res.attributes[1]);
+        let info = mock_info("holder", &coins(10, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "execute"), attr("option_id", "1")]
+        );
+        let mut env = mock_env();
+        env.block.height = 200_000;
+        let info = mock_info("writer", &[]);
+        let res = try_burn(deps.as_mut(), env, info, 2).unwrap();
+        assert_eq!(attr("option_id", "2"), res.attributes[1]);
+    }
+
+    #[test]
+    fn transfer_creator() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // set new owner
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // owner cannot reassign the creator
+        let info = mock_info("owner", &[]);
+        let err = try_transfer_creator(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".into(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // invalid address is rejected
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_creator(deps.as_mut(), mock_env(), info, SINGLETON_ID, "x".into())
+            .unwrap_err();
+
+        // so is the contract itself
+        let info = mock_info("creator", &[]);
+        let err = try_transfer_creator(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "cosmos2contract".into(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::CannotTransferToSelf { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // creator reassigns
+        let info = mock_info("creator", &[]);
+        let res = try_transfer_creator(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "newcreator".into(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "transfer_creator"),
+                attr("option_id", "0"),
+                attr("previous_creator", "creator"),
+                attr("new_creator", "newcreator"),
+            ]
+        );
+        let res: State = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("newcreator", res.creator.as_str());
+        assert_eq!("owner", res.owner.as_str());
+
+        // the creator index follows
+        let mut expired = mock_env();
+        expired.block.height = 100_000;
+        let res =
+            query_creator_claimable(deps.as_ref(), expired.clone(), "creator".into(), None, None)
+                .unwrap();
+        assert_eq!(vec![1], res.ids);
+        let res = query_creator_claimable(deps.as_ref(), expired, "newcreator".into(), None, None)
+            .unwrap();
+        assert_eq!(vec![SINGLETON_ID], res.ids);
+
+        // only the new creator can reassign again
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_creator(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "creator".into(),
+        )
+        .unwrap_err();
+
+        // new creator receives the counter_offer on execute
+        let info = mock_info("owner", &counter_offer);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.messages[0],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "newcreator".into(),
+                amount: counter_offer,
+            })
+        );
+
+        // the short side of an option still held by its creator can be sold too,
+        // without letting the new creator reclaim the collateral
+        let info = mock_info("creator", &[]);
+        let _ =
+            try_transfer_creator(deps.as_mut(), mock_env(), info, 1, "newcreator".into()).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "newcreator".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("newcreator", &[]);
+        let err = try_reclaim(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        match err {
+            ContractError::Transferred {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // new creator is refunded on burn
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("newcreator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, 1).unwrap();
+        assert_eq!(
+            res.messages[0],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "newcreator".into(),
+                amount: coins(2, "BTC"),
+            })
+        );
+    }
+
+    #[test]
+    fn parties() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // fresh option is still held by the creator
+        let res = query_parties(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("creator", res.creator.as_str());
+        assert_eq!("creator", res.owner.as_str());
+        assert!(!res.is_transferred);
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "someone".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let res = query_parties(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(
+            res,
+            PartiesResponse {
+                creator: Addr::unchecked("creator"),
+                owner: Addr::unchecked("someone"),
+                is_transferred: true,
+            }
+        );
+    }
+
+    #[test]
+    fn owner_of_and_creator_of() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let res = query_owner_of(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert_eq!(
+            res,
+            OwnerOfResponse {
+                owner: Addr::unchecked("owner"),
+                status: OptionStatus::Active,
+            }
+        );
+        let res = query_creator_of(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert_eq!(
+            res,
+            CreatorOfResponse {
+                creator: Addr::unchecked("creator"),
+                status: OptionStatus::Active,
+            }
+        );
+
+        // paused, then expired
+        let info = mock_info("creator", &[]);
+        let _ = try_set_paused(deps.as_mut(), mock_env(), info, SINGLETON_ID, true).unwrap();
+        let res = query_owner_of(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert_eq!(OptionStatus::Paused, res.status);
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let res = query_creator_of(deps.as_ref(), env, SINGLETON_ID).unwrap();
+        assert_eq!(OptionStatus::Expired, res.status);
+
+        // missing ids are not found
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::OwnerOf { id: Some(7) }).unwrap_err();
+        match err {
+            StdError::NotFound { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CreatorOf { id: Some(7) },
+        )
+        .unwrap_err();
+        match err {
+            StdError::NotFound { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            exercise_deadline: Some(90_000),
+            allowed_owners: Some(vec!["owner".into()]),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(1, "BTC"), coin(5, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            Some("ticket 1".into()),
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Export { id: None }).unwrap();
+        let blob: Binary = from_binary(&res).unwrap();
+        let state = import_option(blob).unwrap();
+        assert_eq!(query_config(deps.as_ref(), SINGLETON_ID).unwrap(), state);
+
+        // missing ids are not found
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Export { id: Some(7) }).unwrap_err();
+        match err {
+            StdError::NotFound { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn execute() {
+        let mut deps = mock_dependencies(&coins(2, "token"));
+        // // we can just call .unwrap() to assert this was a success
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let _env = mock_env();
+        let info = mock_info("creator", &coins(1, "BTC"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // set new owner
+        let _env = mock_env();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            _env,
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // random cannot execute
+        let info = mock_info("anyone", &[]);
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // expired cannot execute
+        let mut _env = mock_env();
+        _env.block.height = 200_000;
+        let info = mock_info("owner", &counter_offer);
+        let err = try_execute(deps.as_mut(), _env, info, ExecuteOptionMsg::default()).unwrap_err();
+
+        match err {
+            ContractError::Expired { expires } => assert_eq!(100_000, expires),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // bad counter_offer cannot execute
+        let info = mock_info("owner", &coins(39, "ETH"));
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+
+        match err {
+            ContractError::CounterOfferMismatch { missing, extra } => {
+                assert_eq!(coins(1, "ETH"), missing);
+                assert!(extra.is_empty());
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // an extra denom is reported as such
+        let info = mock_info("owner", &[coin(40, "ETH"), coin(5, "ATOM")]);
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::CounterOfferMismatch { missing, extra } => {
+                assert!(missing.is_empty());
+                assert_eq!(coins(5, "ATOM"), extra);
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // as is a surplus of a requested one
+        let info = mock_info("owner", &coins(45, "ETH"));
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::CounterOfferMismatch { missing, extra } => {
+                assert!(missing.is_empty());
+                assert_eq!(coins(5, "ETH"), extra);
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // proper execution
+        let mut _env = mock_env();
+        let info = mock_info("owner", &counter_offer);
+        let res = try_execute(deps.as_mut(), _env, info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: counter_offer,
+            })
+        );
+        assert_eq!(
+            res.messages[1],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".into(),
+                amount: collateral,
+            })
+        );
+
+        // check deleted
+        let err = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+        match err {
+            StdError::NotFound { kind, .. } => assert_eq!("option 0", kind),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a settled option is reported as not found
+        let info = mock_info("owner", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "someone".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::OptionNotFound { id } => assert_eq!(SINGLETON_ID, id),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("owner", &coins(40, "ETH"));
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::OptionNotFound { id } => assert_eq!(SINGLETON_ID, id),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("owner", &[]);
+        let err = try_burn(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::OptionNotFound { id } => assert_eq!(SINGLETON_ID, id),
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn exercise_deadline() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+
+        // the deadline cannot be after expiry
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            exercise_deadline: Some(100_001),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::Std(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("exercise_deadline must not be after expires", msg)
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            exercise_deadline: Some(50_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            exercise_deadline: Some(50_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // executable up to and including the deadline
+        let mut env = mock_env();
+        env.block.height = 50_000;
+        let info = mock_info("creator", &counter_offer);
+        let _ = try_execute(deps.as_mut(), env, info, ExecuteOptionMsg::default()).unwrap();
+
+        // past the deadline but before expiry the window is closed
+        let mut env = mock_env();
+        env.block.height = 50_001;
+        let preview = query_execute_preview(deps.as_ref(), env.clone(), 1).unwrap();
+        assert!(!preview.executable);
+        assert_eq!(49_999, preview.blocks_remaining);
+        let info = mock_info("creator", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let err = try_execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        match err {
+            ContractError::ExerciseWindowClosed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &counter_offer);
+        let err = try_execute_lenient(deps.as_mut(), env, info, 1).unwrap_err();
+        match err {
+            ContractError::ExerciseWindowClosed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn execute_without_collateral() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // collateral drained, e.g. by an earlier partial exercise
+        let mut state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        state.collateral = vec![];
+        options()
+            .save(&mut deps.storage, SINGLETON_ID.into(), &state)
+            .unwrap();
+
+        // the counter_offer is not taken for nothing
+        let info = mock_info("creator", &counter_offer);
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::NoCollateral {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &counter_offer);
+        let err = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NoCollateral {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        assert!(query_config(deps.as_ref(), SINGLETON_ID).is_ok());
+    }
+
+    #[test]
+    fn empty_sends_skipped() {
+        let mut deps = mock_dependencies(&[]);
+
+        // a free option, nothing owed to the creator on execution
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: vec![],
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // only the collateral goes out
+        let info = mock_info("creator", &[]);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral,
+            })]
+        );
+
+        // collateral drained, e.g. by an earlier partial exercise
+        let mut state = query_config(deps.as_ref(), 1).unwrap();
+        state.collateral = vec![coin(0, "BTC")];
+        options().save(&mut deps.storage, 1.into(), &state).unwrap();
+
+        // burning it refunds nothing
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, 1).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(query_config(deps.as_ref(), 1).is_err());
+    }
+
+    #[test]
+    fn collateral_recipient() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("owner", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("owner", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // the recipient must be a valid address
+        let info = mock_info("owner", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            collateral_recipient: Some(Addr::unchecked("x")),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+        // collateral goes to the custom recipient
+        let info = mock_info("owner", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            collateral_recipient: Some(Addr::unchecked("wallet")),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[1],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "wallet".into(),
+                amount: collateral.clone(),
+            })
+        );
+
+        // and to the owner by default
+        let info = mock_info("owner", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[1],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".into(),
+                amount: collateral,
+            })
+        );
+    }
+
+    #[test]
+    fn locked_recipients() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            lock_recipients: true,
+            ..Default::default()
+        };
+        let info = mock_info("owner", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(
+            query_config(deps.as_ref(), SINGLETON_ID)
+                .unwrap()
+                .lock_recipients
+        );
+
+        // the override is ignored, collateral goes to the owner
+        let info = mock_info("owner", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            collateral_recipient: Some(Addr::unchecked("wallet")),
+            ..Default::default()
+        };
+        let res = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "owner".into(),
+                    amount: counter_offer,
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "owner".into(),
+                    amount: collateral,
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn claim_dust() {
+        // recorded: 2BTC and 1BTC collateral, 1BTC extension incentive, a 2ATOM bid
+        // and a 3ATOM auction high bid. The rest is dust
+        let mut deps = mock_dependencies(&[coin(5, "BTC"), coin(10, "ATOM")]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(3, "ETH"),
+            expires: 100_000,
+            admin: Some("admin".into()),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_propose_extension(deps.as_mut(), mock_env(), info, 1, 200_000).unwrap();
+        let info = mock_info("bidder", &coins(2, "ATOM"));
+        let _ = try_place_bid(deps.as_mut(), mock_env(), info, 1, Expiration::Never {}).unwrap();
+
+        // splitting an option rounds its counter_offer but leaves the collateral recorded
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "someone".into(),
+            coins(1, "BTC"),
+        )
+        .unwrap();
+
+        // a running auction escrows its high bid
+        let info = mock_info("creator", &[]);
+        let _ = try_start_auction(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coin(3, "ATOM"),
+            500,
+            13_000,
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("alice", &coins(3, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+
+        // only the admin can claim
+        let info = mock_info("creator", &[]);
+        let err = try_claim_dust(deps.as_mut(), mock_env(), info).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("admin", &[]);
+        let res = try_claim_dust(deps.as_mut(), mock_env(), info).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "admin".into(),
+                amount: vec![coin(5, "ATOM"), coin(1, "BTC")],
+            })]
+        );
+    }
+
+    #[test]
+    fn claim_dust_after_partial_exercise() {
+        // no admin, the creator of the first option claims
+        let mut deps = mock_dependencies(&[coin(10, "BTC")]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(41, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(10, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // 3/10 of the option is split off, its 12.3ETH counter_offer rounded down to 12ETH
+        let info = mock_info("creator", &[]);
+        let res = try_transfer_partial(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "holder".into(),
+            coins(3, "BTC"),
+        )
+        .unwrap();
+        let child_id: u64 = res.attributes[2].value.parse().unwrap();
+        let child = load_option(&deps.storage, child_id).unwrap();
+        assert_eq!(child.counter_offer, coins(12, "ETH"));
+
+        // which the holder exercises, taking 3BTC. A stray 1BTC stays behind with the 7BTC
+        // still recorded for the rest of the option
+        let info = mock_info("holder", &coins(12, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(child_id),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        deps.querier
+            .update_balance("cosmos2contract", vec![coin(8, "BTC")]);
+
+        // nobody else may claim
+        let info = mock_info("holder", &[]);
+        let err = try_claim_dust(deps.as_mut(), mock_env(), info).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // only the dust is sent
+        let info = mock_info("creator", &[]);
+        let res = try_claim_dust(deps.as_mut(), mock_env(), info).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+    }
+
+    #[test]
+    fn validate_addresses() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let validate = |deps: Deps| -> ValidateResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::Validate { id: None }).unwrap()).unwrap()
+        };
+        assert_eq!(
+            validate(deps.as_ref()),
+            ValidateResponse {
+                creator_valid: true,
+                owner_valid: true,
+            }
+        );
+
+        // an owner written without validation, as a faulty migration could
+        let mut state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        state.owner = Addr::unchecked("x");
+        options()
+            .save(&mut deps.storage, SINGLETON_ID.into(), &state)
+            .unwrap();
+        assert_eq!(
+            validate(deps.as_ref()),
+            ValidateResponse {
+                creator_valid: true,
+                owner_valid: false,
+            }
+        );
+    }
+
+    #[test]
+    fn expired_at() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the current height does not matter, expiry is at expires itself
+        for (height, expired) in [
+            (0, false),
+            (99_999, false),
+            (100_000, true),
+            (100_001, true),
+        ] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ExpiredAt { id: None, height },
+            )
+            .unwrap();
+            let res: ExpiredAtResponse = from_binary(&res).unwrap();
+            assert_eq!(expired, res.expired, "height {}", height);
+        }
+        let _ = query_expired_at(deps.as_ref(), 1, 0).unwrap_err();
+    }
+
+    #[test]
+    fn theta_estimate() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 13_345,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let theta = |height: u64| -> ThetaEstimateResponse {
+            let mut env = mock_env();
+            env.block.height = height;
+            let msg = QueryMsg::ThetaEstimate { id: None };
+            from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap()
+        };
+        assert_eq!(
+            theta(12_345),
+            ThetaEstimateResponse {
+                remaining: 1_000,
+                total: 1_000,
+                fraction: Decimal::one(),
+            }
+        );
+        assert_eq!(
+            theta(12_845),
+            ThetaEstimateResponse {
+                remaining: 500,
+                total: 1_000,
+                fraction: Decimal::percent(50),
+            }
+        );
+        for height in [13_345, 20_000] {
+            assert_eq!(
+                theta(height),
+                ThetaEstimateResponse {
+                    remaining: 0,
+                    total: 1_000,
+                    fraction: Decimal::zero(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn can_settle() {
+        let mut deps = mock_dependencies(&[coin(1, "BTC"), coin(5, "ATOM")]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(1, "BTC"), coin(5, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query_can_settle(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert!(res.can_settle);
+
+        // funds siphoned off below the recorded collateral
+        deps.querier
+            .update_balance("cosmos2contract", vec![coin(1, "BTC"), coin(4, "ATOM")]);
+        let res = query_can_settle(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert!(!res.can_settle);
+        deps.querier
+            .update_balance("cosmos2contract", vec![coin(5, "ATOM")]);
+        let res = query_can_settle(deps.as_ref(), mock_env(), SINGLETON_ID).unwrap();
+        assert!(!res.can_settle);
+
+        // a balance shared with another option backs only one of them
+        deps.querier
+            .update_balance("cosmos2contract", vec![coin(2, "BTC"), coin(5, "ATOM")]);
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        for id in [SINGLETON_ID, 1] {
+            let res = query_can_settle(deps.as_ref(), mock_env(), id).unwrap();
+            assert!(!res.can_settle);
+        }
+        deps.querier
+            .update_balance("cosmos2contract", vec![coin(3, "BTC"), coin(5, "ATOM")]);
+        for id in [SINGLETON_ID, 1] {
+            let res = query_can_settle(deps.as_ref(), mock_env(), id).unwrap();
+            assert!(res.can_settle);
+        }
+    }
+
+    #[test]
+    fn dutch_price_rounding() {
+        let price = |start: u128, end: u128, height: u64| {
+            dutch_price(start.into(), end.into(), 10, 13, height)
+                .unwrap()
+                .u128()
+        };
+        // rests at the start price until the auction starts, and at the end price after
+        assert_eq!(price(100, 10, 0), 100);
+        assert_eq!(price(100, 10, 10), 100);
+        assert_eq!(price(100, 10, 13), 10);
+        assert_eq!(price(100, 10, 1_000), 10);
+        // falls linearly in between
+        assert_eq!(price(100, 10, 11), 70);
+        assert_eq!(price(100, 10, 12), 40);
+        // the discount is rounded down, so the price is never below the line
+        assert_eq!(price(10, 0, 11), 7);
+        assert_eq!(price(10, 0, 12), 4);
+        assert_eq!(price(5, 5, 12), 5);
+    }
+
+    #[test]
+    fn dutch_auction() {
+        let listed = || {
+            let mut deps = mock_dependencies(&[]);
+            let msg = InstantiateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 100_000,
+                ..Default::default()
+            };
+            let info = mock_info("creator", &coins(1, "BTC"));
+            let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+            let info = mock_info("creator", &[]);
+            let auction = DutchAuction {
+                start_price: coins(100, "ATOM"),
+                end_price: coins(10, "ATOM"),
+                start_height: 13_000,
+                end_height: 13_090,
+            };
+            let _ = try_list_dutch(deps.as_mut(), mock_env(), info, SINGLETON_ID, auction, None)
+                .unwrap();
+            deps
+        };
+
+        // the price may not rise, nor the auction end before it starts
+        let mut deps = listed();
+        for (end_price, end_height) in [
+            (coins(101, "ATOM"), 13_090),
+            (coins(10, "ETH"), 13_090),
+            (coins(10, "ATOM"), 13_000),
+        ] {
+            let info = mock_info("creator", &[]);
+            let auction = DutchAuction {
+                start_price: coins(100, "ATOM"),
+                end_price,
+                start_height: 13_000,
+                end_height,
+            };
+            let _ = try_list_dutch(deps.as_mut(), mock_env(), info, SINGLETON_ID, auction, None)
+                .unwrap_err();
+        }
+
+        // nothing can be bought before the start
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::AuctionNotStarted { start_height } => assert_eq!(start_height, 13_000),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // paying less than the current price fails, paying more is refunded
+        let mut env = mock_env();
+        env.block.height = 13_030;
+        let info = mock_info("buyer", &coins(69, "ATOM"));
+        let err = try_buy(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::BelowAuctionPrice {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("buyer", &coins(75, "ATOM"));
+        let res = try_buy(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(70, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: coins(5, "ATOM"),
+                }),
+            ]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("buyer"));
+        assert_eq!(state.auction, None);
+
+        // the price at several heights, resting at end_price after the end
+        for (height, price) in [
+            (13_000, 100),
+            (13_001, 99),
+            (13_045, 55),
+            (13_089, 11),
+            (13_090, 10),
+            (50_000, 10),
+        ] {
+            let mut deps = listed();
+            let mut env = mock_env();
+            env.block.height = height;
+            let info = mock_info("buyer", &coins(price, "ATOM"));
+            let res = try_buy(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+            assert_eq!(
+                res.messages,
+                vec![CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(price, "ATOM"),
+                })]
+            );
+        }
+    }
+
+    #[test]
+    fn canonical_coins_check() {
+        assert!(assert_canonical(&[]).is_ok());
+        assert!(assert_canonical(&coins(1, "ATOM")).is_ok());
+        assert!(assert_canonical(&[coin(5, "ATOM"), coin(1, "BTC"), coin(9, "ETH")]).is_ok());
+
+        // out of order, duplicated, or zero
+        for coins in [
+            vec![coin(1, "BTC"), coin(5, "ATOM")],
+            vec![coin(5, "ATOM"), coin(5, "ATOM")],
+            vec![coin(0, "ATOM")],
+            vec![coin(5, "ATOM"), coin(0, "BTC")],
+        ] {
+            match assert_canonical(&coins) {
+                Err(ContractError::NonCanonicalCoins {}) => {}
+                res => panic!("unexpected: {:?}", res),
+            }
+        }
+
+        // options keep their coins canonical however they were given
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            counter_offer: vec![coin(40, "ETH"), coin(0, "BTC"), coin(3, "ATOM")],
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(1, "BTC"), coin(5, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(vec![coin(5, "ATOM"), coin(1, "BTC")], state.collateral);
+        assert_eq!(vec![coin(3, "ATOM"), coin(40, "ETH")], state.counter_offer);
+    }
+
+    #[test]
+    fn royalty_math() {
+        let (proceeds, royalty) =
+            split_price(vec![coin(999, "ATOM"), coin(5, "ETH")].into(), 250).unwrap();
+        // rounded down, and the ETH royalty rounds to nothing
+        assert_eq!(proceeds, vec![coin(975, "ATOM"), coin(5, "ETH")].into());
+        assert_eq!(royalty, coins(24, "ATOM").into());
+
+        let token = Cw20CoinVerified {
+            address: Addr::unchecked("token"),
+            amount: Uint128::from(1_000u128),
+        };
+        let (proceeds, royalty) = split_price(token.into(), 250).unwrap();
+        assert_eq!(
+            proceeds,
+            Cw20CoinVerified {
+                address: Addr::unchecked("token"),
+                amount: Uint128::from(975u128),
+            }
+            .into()
+        );
+        assert_eq!(
+            royalty,
+            Cw20CoinVerified {
+                address: Addr::unchecked("token"),
+                amount: Uint128::from(25u128),
+            }
+            .into()
+        );
+
+        let (proceeds, royalty) = split_price(coins(7, "ATOM").into(), 0).unwrap();
+        assert_eq!(proceeds, coins(7, "ATOM").into());
+        assert!(royalty.is_empty());
+    }
+
+    #[test]
+    fn royalties() {
+        let mut deps = mock_dependencies(&[]);
+
+        // the royalty is capped
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            royalty_bps: MAX_ROYALTY_BPS + 1,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            royalty_bps: 500,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // plain transfers pay no royalty
+        for id in [SINGLETON_ID, 1] {
+            let info = mock_info("creator", &[]);
+            let res = try_transfer(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                id,
+                "seller".into(),
+                None,
+                None,
+            )
+            .unwrap();
+            assert!(res.messages.is_empty());
+            let info = mock_info("seller", &[]);
+            let _ = try_list(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                id,
+                coins(100, "ATOM"),
+                None,
+            )
+            .unwrap();
+        }
+
+        // a sale splits the price between the seller and the creator
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "seller".into(),
+                    amount: coins(95, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(5, "ATOM"),
+                }),
+            ]
+        );
+        assert!(res.attributes.contains(&attr("proceeds", "95ATOM")));
+        assert!(res.attributes.contains(&attr("royalty", "5ATOM")));
+
+        // so does an accepted bid
+        let info = mock_info("bidder", &coins(40, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let res = try_accept_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "bidder".into(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: coins(38, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(2, "ATOM"),
+                }),
+            ]
+        );
+
+        // options without a royalty pay the seller in full
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let res = try_buy(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "seller".into(),
+                amount: coins(100, "ATOM"),
+            })]
+        );
+        assert!(!res.attributes.iter().any(|a| a.key == "royalty"));
+    }
+
+    #[test]
+    fn market_fee() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            admin: Some("admin".into()),
+            royalty_bps: 500,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the admin sets the fee, within the cap and with a treasury
+        let info = mock_info("creator", &[]);
+        let err = try_set_market_fee(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            100,
+            Some("treasury".into()),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("admin", &[]);
+        let _ = try_set_market_fee(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            MAX_MARKET_FEE_BPS + 1,
+            Some("treasury".into()),
+        )
+        .unwrap_err();
+        let info = mock_info("admin", &[]);
+        let _ = try_set_market_fee(deps.as_mut(), mock_env(), info, 100, None).unwrap_err();
+        let info = mock_info("admin", &[]);
+        let _ = try_set_market_fee(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            100,
+            Some("treasury".into()),
+        )
+        .unwrap();
+        let settings = query_settings(deps.as_ref()).unwrap();
+        assert_eq!(100, settings.market_fee_bps);
+        assert_eq!(Some(Addr::unchecked("treasury")), settings.treasury);
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "seller".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        let price = vec![coin(1_000, "ATOM"), coin(50, "ETH")];
+        let info = mock_info("seller", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap();
+
+        // the fee comes off first, then the royalty off the rest, both rounded down:
+        // 1% of 50ETH rounds to nothing
+        let info = mock_info("buyer", &price);
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "seller".into(),
+                    amount: vec![coin(941, "ATOM"), coin(48, "ETH")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "treasury".into(),
+                    amount: coins(10, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: vec![coin(49, "ATOM"), coin(2, "ETH")],
+                }),
+            ]
+        );
+        assert!(res.attributes.contains(&attr("proceeds", "941ATOM,48ETH")));
+        assert!(res.attributes.contains(&attr("market_fee", "10ATOM")));
+        assert!(res.attributes.contains(&attr("royalty", "49ATOM,2ETH")));
+
+        // 1% of 99ATOM rounds to nothing, leaving the seller and the royalty
+        let info = mock_info("bidder", &coins(99, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let res = try_accept_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "bidder".into(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: coins(95, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(4, "ATOM"),
+                }),
+            ]
+        );
+        assert!(!res.attributes.iter().any(|a| a.key == "market_fee"));
+    }
+
+    #[test]
+    fn primary_sale() {
+        let mut deps = mock_dependencies(&[]);
+
+        // a premium must be a valid price
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            premium: Some(coins(0, "ATOM")),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            premium: Some(coins(10, "ATOM")),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            premium: Some(coins(10, "ATOM")),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // underpaying, or paying in another denom, is rejected
+        for funds in [coins(9, "ATOM"), coins(10, "OSMO")] {
+            let info = mock_info("buyer", &funds);
+            let err = try_buy_primary(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+            assert!(matches!(err, ContractError::BelowPremium {}));
+        }
+
+        // the first buyer pays the creator, and gets back what it sent beyond the premium
+        let info = mock_info("buyer", &[coin(12, "ATOM"), coin(3, "OSMO")]);
+        let res = try_buy_primary(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(10, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: vec![coin(2, "ATOM"), coin(3, "OSMO")],
+                }),
+            ]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("buyer"));
+        assert_eq!(state.premium, None);
+
+        // after which the offer is gone
+        let info = mock_info("other", &coins(10, "ATOM"));
+        let err = try_buy_primary(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::NotListed {}));
+
+        // the creator can cancel an unsold offer and take back the collateral
+        let info = mock_info("creator", &[]);
+        let res = try_reclaim(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+        let info = mock_info("buyer", &coins(10, "ATOM"));
+        let err = try_buy_primary(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        assert!(matches!(err, ContractError::OptionNotFound { id: 1 }));
+    }
+
+    #[test]
+    fn bundles() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // ids 1 to 3, the last one held by someone else
+        for _ in 0..3 {
+            let create = CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 100_000,
+                royalty_bps: 1_000,
+                ..Default::default()
+            };
+            let info = mock_info("creator", &coins(1, "BTC"));
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            3,
+            "other".into(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // every leg must be the seller's, distinct, and more than one
+        let info = mock_info("creator", &[]);
+        let err = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![0, 3],
+            coins(100, "ATOM"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        for ids in [vec![0], vec![0, 0]] {
+            let info = mock_info("creator", &[]);
+            let _ = try_list_bundle(deps.as_mut(), mock_env(), info, ids, coins(100, "ATOM"))
+                .unwrap_err();
+        }
+        let info = mock_info("creator", &[]);
+        let res = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![0, 1],
+            coins(100, "ATOM"),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("bundle_id", "0")));
+        let info = mock_info("creator", &[]);
+        let res = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![1, 2],
+            coins(100, "ATOM"),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("bundle_id", "1")));
+
+        // listing leg 1 again dropped it from the first bundle
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let err = try_buy_bundle(deps.as_mut(), mock_env(), info, 0).unwrap_err();
+        assert!(matches!(err, ContractError::BundleInvalid {}));
+
+        // the second sells whole, for exactly its price
+        let info = mock_info("buyer", &coins(99, "ATOM"));
+        let err = try_buy_bundle(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        assert!(matches!(err, ContractError::WrongPrice {}));
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let res = try_buy_bundle(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(100, "ATOM"),
+            })]
+        );
+        for id in [1, 2] {
+            let state = load_option(&deps.storage, id).unwrap();
+            assert_eq!(state.owner, Addr::unchecked("buyer"));
+            assert_eq!(state.bundle, None);
+        }
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let err = try_buy_bundle(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::BundleNotFound { bundle_id: 1 }
+        ));
+
+        // resold, each leg's royalty is taken on half the price
+        let info = mock_info("buyer", &[]);
+        let _ = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![1, 2],
+            coins(101, "ATOM"),
+        )
+        .unwrap();
+        let info = mock_info("third", &coins(101, "ATOM"));
+        let res = try_buy_bundle(deps.as_mut(), mock_env(), info, 2).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".into(),
+                    amount: coins(91, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(5, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(5, "ATOM"),
+                }),
+            ]
+        );
+
+        // exercising a leg invalidates its bundle
+        let info = mock_info("third", &[]);
+        let _ = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![1, 2],
+            coins(100, "ATOM"),
+        )
+        .unwrap();
+        let info = mock_info("third", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(2),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("buyer", &coins(100, "ATOM"));
+        let err = try_buy_bundle(deps.as_mut(), mock_env(), info, 3).unwrap_err();
+        assert!(matches!(err, ContractError::BundleInvalid {}));
+    }
+
+    #[test]
+    fn buy_after_expiry() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            premium: Some(coins(3, "ATOM")),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for _ in 0..2 {
+            let create = CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 100_000,
+                ..Default::default()
+            };
+            let info = mock_info("creator", &coins(1, "BTC"));
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+        let info = mock_info("creator", &[]);
+        let _ = try_list_bundle(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![1, 2],
+            coins(9, "ATOM"),
+        )
+        .unwrap();
+        let mut expired = mock_env();
+        expired.block.height = 100_000;
+
+        // an expired option is no longer sold at its premium, in a bundle or listed
+        let info = mock_info("buyer", &coins(3, "ATOM"));
+        let err = try_buy_primary(deps.as_mut(), expired.clone(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { expires: 100_000 }));
+        let info = mock_info("buyer", &coins(9, "ATOM"));
+        let err = try_buy_bundle(deps.as_mut(), expired.clone(), info, 0).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { expires: 100_000 }));
+        let info = mock_info("creator", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(5, "ATOM"),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("buyer", &coins(5, "ATOM"));
+        let err = try_buy(deps.as_mut(), expired.clone(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { expires: 100_000 }));
+        let info = mock_info("creator", &[]);
+        let _ = try_list_cw20(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "token".into(),
+            Uint128::from(5u128),
+            None,
+        )
+        .unwrap();
+        let paid = Cw20CoinVerified {
+            address: Addr::unchecked("token"),
+            amount: Uint128::from(5u128),
+        };
+        let buyer = Addr::unchecked("buyer");
+        let err = try_buy_cw20(deps.as_mut(), expired, buyer, SINGLETON_ID, paid).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { expires: 100_000 }));
+    }
+
+    #[test]
+    fn private_listing() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let price = coins(5, "ATOM");
+        let info = mock_info("creator", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            Some("friend".into()),
+        )
+        .unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(Some(Addr::unchecked("friend")), state.private_to);
+
+        // only the designated buyer can buy
+        let info = mock_info("buyer", &price);
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::PrivateListing {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("friend", &price);
+        let _ = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("friend", state.owner.as_str());
+        assert_eq!(None, state.private_to);
+
+        // the owner can open a private listing to anyone
+        let info = mock_info("friend", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            Some("creator".into()),
+        )
+        .unwrap();
+        let info = mock_info("buyer", &price);
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::PrivateListing {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("friend", &[]);
+        let _ = try_update_listing(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("buyer", &price);
+        let _ = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("buyer", state.owner.as_str());
+    }
+
+    #[test]
+    fn list_and_buy() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let price = coins(5, "ATOM");
+
+        // an unlisted option cannot be bought
+        let info = mock_info("buyer", &price);
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // only the owner can list
+        let info = mock_info("buyer", &[]);
+        let err = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap();
+
+        // the price must be paid exactly
+        for funds in [coins(4, "ATOM"), coins(6, "ATOM"), coins(5, "ETH")] {
+            let info = mock_info("buyer", &funds);
+            let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+            match err {
+                ContractError::WrongPrice {} => {}
+                e => panic!("unexpected: {}", e),
+            }
+        }
+
+        // the seller is paid and the buyer becomes the owner
+        let info = mock_info("buyer", &price);
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: price.clone(),
+            })]
+        );
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("buyer", state.owner.as_str());
+        assert_eq!(None, state.ask);
+
+        // a transfer drops the listing
+        let info = mock_info("buyer", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("buyer", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "friend".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("other", &price);
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // as does settling the option
+        let info = mock_info("friend", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            price.clone(),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("friend", &coins(40, "ETH"));
+        let _ = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let info = mock_info("other", &price);
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::OptionNotFound { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn listings() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for creator in ["creator", "maker", "creator"] {
+            let create = CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires: 100_000,
+                ..Default::default()
+            };
+            let info = mock_info(creator, &coins(2, "BTC"));
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+        // options 0 to 2 are listed, 3 is not
+        for (id, owner) in [(0, "creator"), (1, "creator"), (2, "maker")] {
+            let info = mock_info(owner, &[]);
+            let _ = try_list(deps.as_mut(), mock_env(), info, id, coins(5, "ATOM"), None).unwrap();
+        }
+
+        let listings = |deps: Deps, seller: Option<&str>, start_after, limit| -> Vec<u64> {
+            let msg = QueryMsg::Listings {
+                seller: seller.map(String::from),
+                start_after,
+                limit,
+            };
+            let res: ListingsResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.listings.into_iter().map(|listing| listing.id).collect()
+        };
+        assert_eq!(listings(deps.as_ref(), None, None, None), vec![0, 1, 2]);
+        assert_eq!(listings(deps.as_ref(), None, None, Some(2)), vec![0, 1]);
+        assert_eq!(listings(deps.as_ref(), None, Some(1), None), vec![2]);
+        assert_eq!(
+            listings(deps.as_ref(), Some("creator"), None, None),
+            vec![0, 1]
+        );
+        let res: ListingsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Listings {
+                    seller: Some("maker".into()),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.listings,
+            vec![Listing {
+                id: 2,
+                seller: Addr::unchecked("maker"),
+                price: ListingPrice::Native(coins(5, "ATOM")),
+                expires: 100_000,
+                collateral: coins(2, "BTC"),
+                private_to: None,
+            }]
+        );
+
+        // bought and delisted options drop out
+        let info = mock_info("buyer", &coins(5, "ATOM"));
+        let _ = try_buy(deps.as_mut(), mock_env(), info, 0).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_delist(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(listings(deps.as_ref(), None, None, None), vec![2]);
+        assert!(listings(deps.as_ref(), Some("creator"), None, None).is_empty());
+
+        // as do options in an English auction, under their seller, and offers at a premium
+        let info = mock_info("buyer", &[]);
+        let _ = try_start_auction(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            0,
+            coin(10, "ATOM"),
+            500,
+            13_000,
+            None,
+            None,
+        )
+        .unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            premium: Some(coins(3, "ATOM")),
+            ..Default::default()
+        };
+        let info = mock_info("maker", &coins(2, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        assert_eq!(listings(deps.as_ref(), None, None, None), vec![0, 2, 4]);
+        assert_eq!(listings(deps.as_ref(), Some("buyer"), None, None), vec![0]);
+        let res: ListingsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Listings {
+                    seller: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let auction = query_auction(deps.as_ref(), 0).unwrap().unwrap();
+        assert_eq!(res.listings[0].price, ListingPrice::Auction(auction));
+        assert_eq!(
+            res.listings[2].price,
+            ListingPrice::Primary(coins(3, "ATOM"))
+        );
+        assert_eq!(res.listings[2].seller, Addr::unchecked("maker"));
+    }
+
+    #[test]
+    fn update_listing_and_delist() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // nothing to update before listing
+        let info = mock_info("creator", &[]);
+        let err = try_update_listing(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(5, "ATOM"),
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(5, "ATOM"),
+            None,
+        )
+        .unwrap();
+
+        // non-owners cannot touch the listing
+        let info = mock_info("buyer", &[]);
+        let err = try_update_listing(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(1, "ATOM"),
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("buyer", &[]);
+        let err = try_delist(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the old price no longer buys the option once updated
+        let info = mock_info("creator", &[]);
+        let _ = try_update_listing(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(7, "ATOM"),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("buyer", &coins(5, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::WrongPrice {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // delisted options cannot be bought
+        let info = mock_info("creator", &[]);
+        let res = try_delist(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert!(res.messages.is_empty());
+        let info = mock_info("buyer", &coins(7, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // relisted and updated, the new price buys it
+        let info = mock_info("creator", &[]);
+        let _ = try_list(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(5, "ATOM"),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_update_listing(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coins(9, "ATOM"),
+            None,
+        )
+        .unwrap();
+        let info = mock_info("buyer", &coins(9, "ATOM"));
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(9, "ATOM"),
+            })]
+        );
+        assert_eq!(
+            "buyer",
+            query_config(deps.as_ref(), SINGLETON_ID)
+                .unwrap()
+                .owner
+                .as_str()
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for (collateral, counter_offer, expires) in [
+            (coins(2, "BTC"), coins(10, "ETH"), 100_000),
+            (coins(2, "BTC"), coins(10, "ETH"), 200_000),
+        ] {
+            let create = CreateMsg {
+                counter_offer,
+                expires,
+                ..Default::default()
+            };
+            let info = mock_info("creator", &collateral);
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+
+        // only the owner of both can merge
+        let info = mock_info("anyone", &[]);
+        let err = try_merge(deps.as_mut(), mock_env(), info, SINGLETON_ID, 1).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // amounts add up, and the other option is gone
+        let info = mock_info("creator", &[]);
+        let _ = try_merge(deps.as_mut(), mock_env(), info, SINGLETON_ID, 1).unwrap();
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.collateral, coins(3, "BTC"));
+        assert_eq!(state.counter_offer, coins(50, "ETH"));
+        let _ = load_option(&deps.storage, 1).unwrap_err();
+
+        // options expiring at different heights do not merge
+        let info = mock_info("creator", &[]);
+        let err = try_merge(deps.as_mut(), mock_env(), info, SINGLETON_ID, 2).unwrap_err();
+        match err {
+            ContractError::IncompatibleOptions {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let state = load_option(&deps.storage, 2).unwrap();
+        assert_eq!(state.collateral, coins(2, "BTC"));
+
+        // nor do options with a different royalty or lock-up
+        for create in [
+            CreateMsg {
+                counter_offer: coins(10, "ETH"),
+                expires: 100_000,
+                royalty_bps: 500,
+                ..Default::default()
+            },
+            CreateMsg {
+                counter_offer: coins(10, "ETH"),
+                expires: 100_000,
+                transfer_lock_until: Some(50_000),
+                ..Default::default()
+            },
+        ] {
+            let info = mock_info("creator", &coins(2, "BTC"));
+            let res = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+            let id: u64 = res.attributes[1].value.parse().unwrap();
+            let info = mock_info("creator", &[]);
+            let err = try_merge(deps.as_mut(), mock_env(), info, SINGLETON_ID, id).unwrap_err();
+            match err {
+                ContractError::IncompatibleOptions {} => {}
+                e => panic!("unexpected: {}", e),
+            }
+        }
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.royalty_bps, 0);
+        assert_eq!(state.transfer_lock_until, None);
+    }
+
+    #[test]
+    fn set_memo() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the owner can annotate
+        let info = mock_info("anyone", &[]);
+        let err = try_set_memo(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Some("mine".into()),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // oversized memos are rejected
+        let info = mock_info("creator", &[]);
+        let err = try_set_memo(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Some("x".repeat(MAX_MEMO_LENGTH + 1)),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::MemoTooLong {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // set, up to the limit, shown in config
+        let info = mock_info("creator", &[]);
+        let _ = try_set_memo(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Some("x".repeat(MAX_MEMO_LENGTH)),
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_set_memo(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Some("hedge for loan X".into()),
+        )
+        .unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(Some("hedge for loan X".to_string()), state.memo);
+
+        // and cleared
+        let info = mock_info("creator", &[]);
+        let _ = try_set_memo(deps.as_mut(), mock_env(), info, SINGLETON_ID, None).unwrap();
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(None, state.memo);
+    }
+
+    #[test]
+    fn offers_on_unlisted_options() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // neither option is listed, offers are taken all the same
+        for id in [SINGLETON_ID, 1] {
+            for (bidder, amount) in [("alice", 7), ("bob", 9)] {
+                let info = mock_info(bidder, &coins(amount, "ATOM"));
+                let _ = try_place_bid(
+                    deps.as_mut(),
+                    mock_env(),
+                    info,
+                    id,
+                    Expiration::AtHeight(50_000),
+                )
+                .unwrap();
+            }
+        }
+        let info = mock_info("creator", &[]);
+        let res =
+            try_accept_bid(deps.as_mut(), mock_env(), info, SINGLETON_ID, "bob".into()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(9, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".into(),
+                    amount: coins(7, "ATOM"),
+                }),
+            ]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("bob"));
+
+        // exercise leaves the offers on the other option unacceptable, though unexpired,
+        // and anyone can refund them
+        let info = mock_info("creator", &coins(40, "ETH"));
+        let msg = ExecuteOptionMsg {
+            id: Some(1),
+            ..Default::default()
+        };
+        let _ = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_accept_bid(deps.as_mut(), mock_env(), info, 1, "bob".into()).unwrap_err();
+        let info = mock_info("anyone", &[]);
+        let res = try_refund_expired_bids(deps.as_mut(), mock_env(), info, 1, None).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".into(),
+                    amount: coins(7, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".into(),
+                    amount: coins(9, "ATOM"),
+                }),
+            ]
+        );
+        assert!(res.attributes.contains(&attr("refunded", "2")));
+        let info = mock_info("bob", &[]);
+        let err = try_withdraw_bid(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        assert!(matches!(err, ContractError::NoBid {}));
+    }
+
+    #[test]
+    fn expired_bids() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a bid cannot already be expired
+        let info = mock_info("alice", &coins(3, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::AtHeight(mock_env().block.height),
+        )
+        .unwrap_err();
+
+        let info = mock_info("alice", &coins(3, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::AtHeight(13_000),
+        )
+        .unwrap();
+        let info = mock_info("bob", &coins(2, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+
+        // nothing to refund while the bid is live
+        let mut env = mock_env();
+        let info = mock_info("keeper", &[]);
+        let res =
+            try_refund_expired_bids(deps.as_mut(), env.clone(), info, SINGLETON_ID, None).unwrap();
+        assert!(res.messages.is_empty());
+
+        // the owner cannot accept once it expired
+        env.block.height = 13_000;
+        let info = mock_info("creator", &[]);
+        let err = try_accept_bid(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            SINGLETON_ID,
+            "alice".into(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ExpiredBid {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // anyone can refund it, and the funds go back to the bidder
+        let info = mock_info("keeper", &coins(1, "ATOM"));
+        let _ = try_refund_expired_bids(deps.as_mut(), env.clone(), info, SINGLETON_ID, None)
+            .unwrap_err();
+        let info = mock_info("keeper", &[]);
+        let res =
+            try_refund_expired_bids(deps.as_mut(), env.clone(), info, SINGLETON_ID, None).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(3, "ATOM"),
+            })]
+        );
+        assert_eq!(
+            res.attributes
+                .iter()
+                .find(|a| a.key == "refunded")
+                .unwrap()
+                .value,
+            "1"
+        );
+        assert!(BIDS
+            .may_load(
+                &deps.storage,
+                (SINGLETON_ID.into(), &Addr::unchecked("alice"))
+            )
+            .unwrap()
+            .is_none());
+
+        // the live bid is untouched and can still be accepted
+        let info = mock_info("creator", &[]);
+        let _ = try_accept_bid(deps.as_mut(), env, info, SINGLETON_ID, "bob".into()).unwrap();
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("bob"));
+    }
+
+    fn auctioned(buy_now: Option<Coin>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_start_auction(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coin(10, "ATOM"),
+            500,
+            13_000,
+            buy_now,
+            None,
+        )
+        .unwrap();
+        deps
+    }
+
+    #[test]
+    fn english_auction() {
+        let mut deps = auctioned(None);
+
+        // the contract holds the option meanwhile
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, mock_env().contract.address);
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "friend".into(),
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        // bids below the reserve are taken, but the seller cannot bid, nor in another denom
+        let info = mock_info("alice", &coins(5, "ATOM"));
+        let res = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert!(res.messages.is_empty());
+        let info = mock_info("creator", &coins(50, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        let info = mock_info("bob", &coins(50, "ETH"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+
+        // a new bid must raise by 5%, rounded up, and refunds the one it outbids
+        let info = mock_info("bob", &coins(5, "ATOM"));
+        let err = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::BidTooLow { minimum } => assert_eq!(minimum, coin(6, "ATOM")),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("bob", &coins(6, "ATOM"));
+        let res = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(5, "ATOM"),
+            })]
+        );
+
+        // a bid in the last blocks extends the auction
+        let mut env = mock_env();
+        env.block.height = 12_995;
+        let info = mock_info("carol", &coins(20, "ATOM"));
+        let res = try_bid_auction(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".into(),
+                amount: coins(6, "ATOM"),
+            })]
+        );
+        let auction: AuctionResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Auction { id: None }).unwrap())
+                .unwrap();
+        assert_eq!(auction.unwrap().end_height, 13_010);
+
+        // it cannot be settled before the extended end
+        env.block.height = 13_000;
+        let info = mock_info("anyone", &[]);
+        let err = try_settle_auction(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::AuctionNotEnded { end_height } => assert_eq!(end_height, 13_010),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("alice", &coins(30, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap();
+
+        // then anyone settles it, paying the seller and handing the option to the winner
+        env.block.height = 13_020;
+        let info = mock_info("alice", &coins(40, "ATOM"));
+        let err = try_bid_auction(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::AuctionEnded {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("anyone", &[]);
+        let res = try_settle_auction(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(30, "ATOM"),
+            })]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("alice"));
+        let info = mock_info("anyone", &[]);
+        let err = try_settle_auction(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NoAuction {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn auction_near_expiry() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 13_010,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_start_auction(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coin(10, "ATOM"),
+            500,
+            13_005,
+            None,
+            None,
+        )
+        .unwrap();
+        let bid_at = |deps: &mut OwnedDeps<_, _, _>, bidder: &str, amount: u128, height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            let info = mock_info(bidder, &coins(amount, "ATOM"));
+            try_bid_auction(deps.as_mut(), env, info, SINGLETON_ID)
+        };
+
+        // late bids extend the auction only up to the block before expiry
+        let res = bid_at(&mut deps, "alice", 10, 13_000).unwrap();
+        assert!(res.attributes.contains(&attr("end_height", "13009")));
+        let res = bid_at(&mut deps, "bob", 11, 13_008).unwrap();
+        assert!(!res.attributes.iter().any(|a| a.key == "end_height"));
+        let auction = AUCTIONS.load(&deps.storage, SINGLETON_ID.into()).unwrap();
+        assert_eq!(13_009, auction.end_height);
+
+        // no bids come in once the option expired
+        let err = bid_at(&mut deps, "carol", 20, 13_010).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { expires: 13_010 }));
+
+        // settling after expiry refunds the high bid and returns the option to the seller
+        let mut env = mock_env();
+        env.block.height = 13_010;
+        let info = mock_info("anyone", &[]);
+        let res = try_settle_auction(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert!(res.attributes.contains(&attr("winner", "none")));
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".into(),
+                amount: coins(11, "ATOM"),
+            })]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!("creator", state.owner.as_str());
+    }
+
+    #[test]
+    fn auction_buy_now() {
+        // before any bid, buying now ends the auction
+        let mut deps = auctioned(Some(coin(25, "ATOM")));
+        let info = mock_info("alice", &coins(20, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::WrongPrice {}));
+        let info = mock_info("alice", &coins(25, "ATOM"));
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(25, "ATOM"),
+            })]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("alice"));
+        assert_eq!(None, query_auction(deps.as_ref(), SINGLETON_ID).unwrap());
+
+        // after a lower bid, which is refunded
+        let mut deps = auctioned(Some(coin(25, "ATOM")));
+        let info = mock_info("bob", &coins(20, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let info = mock_info("alice", &coins(25, "ATOM"));
+        let res = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(25, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".into(),
+                    amount: coins(20, "ATOM"),
+                }),
+            ]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("alice"));
+        let info = mock_info("anyone", &[]);
+        let mut env = mock_env();
+        env.block.height = 13_000;
+        let err = try_settle_auction(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::NoAuction {}));
+
+        // not after a bid reaching the price, the auction runs on
+        let mut deps = auctioned(Some(coin(25, "ATOM")));
+        let info = mock_info("bob", &coins(30, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let info = mock_info("alice", &coins(25, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::BuyNowOutbid {}));
+        let auction = query_auction(deps.as_ref(), SINGLETON_ID).unwrap().unwrap();
+        assert_eq!(
+            auction.high_bid,
+            Some(AuctionBid {
+                bidder: Addr::unchecked("bob"),
+                amount: coin(30, "ATOM"),
+            })
+        );
+
+        // nor once the auction ended
+        let mut deps = auctioned(Some(coin(25, "ATOM")));
+        let mut env = mock_env();
+        env.block.height = 13_000;
+        let info = mock_info("alice", &coins(25, "ATOM"));
+        let err = try_buy(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::AuctionEnded {}));
+    }
+
+    #[test]
+    fn private_auction() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_start_auction(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            coin(10, "ATOM"),
+            500,
+            13_000,
+            Some(coin(25, "ATOM")),
+            Some("friend".into()),
+        )
+        .unwrap();
+
+        // strangers can neither bid nor buy now
+        let info = mock_info("stranger", &coins(20, "ATOM"));
+        let err = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::PrivateListing {}));
+        let info = mock_info("stranger", &coins(25, "ATOM"));
+        let err = try_buy(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        assert!(matches!(err, ContractError::PrivateListing {}));
+
+        // the designated buyer wins it at settlement
+        let info = mock_info("friend", &coins(20, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        let mut env = mock_env();
+        env.block.height = 13_000;
+        let info = mock_info("anyone", &[]);
+        let _ = try_settle_auction(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("friend"));
+    }
+
+    #[test]
+    fn english_auction_reserve_not_met() {
+        let mut deps = auctioned(None);
+
+        let info = mock_info("alice", &coins(9, "ATOM"));
+        let _ = try_bid_auction(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+
+        // the high bid is refunded and the option returns to the seller
+        let mut env = mock_env();
+        env.block.height = 13_000;
+        let info = mock_info("anyone", &[]);
+        let res = try_settle_auction(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(9, "ATOM"),
+            })]
+        );
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, Addr::unchecked("creator"));
+        assert!(AUCTIONS
+            .may_load(&deps.storage, SINGLETON_ID.into())
+            .unwrap()
+            .is_none());
+
+        // having never been sold, it can still be reclaimed
+        assert!(!state.transferred);
+        let info = mock_info("creator", &[]);
+        let res = try_reclaim(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+    }
+
+    #[test]
+    fn query_bids() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        for (bidder, id, amount, valid_until) in [
+            ("carol", 0, 3, Expiration::Never {}),
+            ("alice", 0, 2, Expiration::Never {}),
+            ("bob", 0, 4, Expiration::AtHeight(13_000)),
+            ("alice", 1, 5, Expiration::Never {}),
+        ] {
+            let info = mock_info(bidder, &coins(amount, "ATOM"));
+            let _ = try_place_bid(deps.as_mut(), mock_env(), info, id, valid_until).unwrap();
+        }
+
+        let mut env = mock_env();
+        env.block.height = 13_000;
+        let bids = |msg: QueryMsg| -> Vec<BidInfo> {
+            let res: BidsResponse =
+                from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+            res.bids
+        };
+
+        // sorted by bidder, the expired bid flagged but still listed
+        let res = bids(QueryMsg::Bids {
+            id: None,
+            start_after: None,
+            limit: None,
+        });
+        let summary: Vec<(&str, bool)> = res
+            .iter()
+            .map(|bid| (bid.bidder.as_str(), bid.expired))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("alice", false), ("bob", true), ("carol", false)]
+        );
+        assert_eq!(
+            res[1],
+            BidInfo {
+                id: 0,
+                bidder: Addr::unchecked("bob"),
+                amount: Balance::from(coins(4, "ATOM")),
+                valid_until: Expiration::AtHeight(13_000),
+                expired: true,
+            }
+        );
+        let res = bids(QueryMsg::Bids {
+            id: None,
+            start_after: Some("alice".into()),
+            limit: Some(1),
+        });
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].bidder, Addr::unchecked("bob"));
+
+        // a bidder finds its bids on every option
+        let res = bids(QueryMsg::BidsByBidder {
+            bidder: "alice".into(),
+            start_after: None,
+            limit: None,
+        });
+        let summary: Vec<(u64, Balance)> =
+            res.into_iter().map(|bid| (bid.id, bid.amount)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, Balance::from(coins(2, "ATOM"))),
+                (1, Balance::from(coins(5, "ATOM")))
+            ]
+        );
+        let res = bids(QueryMsg::BidsByBidder {
+            bidder: "alice".into(),
+            start_after: Some(0),
+            limit: None,
+        });
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 1);
+        let res = bids(QueryMsg::BidsByBidder {
+            bidder: "dave".into(),
+            start_after: None,
+            limit: None,
+        });
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn bids() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // a bid needs funds, and the owner cannot bid
+        let info = mock_info("alice", &[]);
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        let info = mock_info("creator", &coins(3, "ATOM"));
+        let err = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // nor can anyone bid on an expired option
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("alice", &coins(3, "ATOM"));
+        let err = try_place_bid(deps.as_mut(), env, info, SINGLETON_ID, Expiration::Never {})
+            .unwrap_err();
+        match err {
+            ContractError::Expired { expires } => assert_eq!(100_000, expires),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a new bid replaces and refunds the bidder's previous one
+        let info = mock_info("alice", &coins(3, "ATOM"));
+        let res = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        let info = mock_info("alice", &coins(4, "ATOM"));
+        let res = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(3, "ATOM"),
+            })]
+        );
+
+        // but a contract posing as a token cannot swap it for a token bid
+        let info = mock_info("fake", &[]);
+        let receive = Cw20ReceiveMsg {
+            sender: "alice".into(),
+            amount: Uint128::from(1_000u128),
+            msg: to_binary(&Cw20HookMsg::PlaceBid {
+                id: None,
+                valid_until: None,
+            })
+            .unwrap(),
+        };
+        let _ = try_receive_cw20(deps.as_mut(), mock_env(), info, receive).unwrap_err();
+        let bid = BIDS
+            .load(
+                &deps.storage,
+                (SINGLETON_ID.into(), &Addr::unchecked("alice")),
+            )
+            .unwrap();
+        assert_eq!(bid.amount, Balance::from(coins(4, "ATOM")));
+
+        let info = mock_info("bob", &coins(2, "ATOM"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+        let info = mock_info("carol", &coins(1, "ETH"));
+        let _ = try_place_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Expiration::Never {},
+        )
+        .unwrap();
+
+        // only the owner accepts, and only an outstanding bid
+        let info = mock_info("bob", &[]);
+        let err = try_accept_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "alice".into(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &[]);
+        let err = try_accept_bid(deps.as_mut(), mock_env(), info, SINGLETON_ID, "dave".into())
+            .unwrap_err();
+        match err {
+            ContractError::NoBid {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // nor once the option expired
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let err =
+            try_accept_bid(deps.as_mut(), env, info, SINGLETON_ID, "alice".into()).unwrap_err();
+        match err {
+            ContractError::Expired { expires } => assert_eq!(100_000, expires),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the owner is paid alice's bid and everyone else is refunded
+        let info = mock_info("creator", &[]);
+        let res = try_accept_bid(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "alice".into(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(4, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".into(),
+                    amount: coins(2, "ATOM"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "carol".into(),
+                    amount: coins(1, "ETH"),
+                }),
+            ]
+        );
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("alice", state.owner.as_str());
+        for bidder in ["alice", "bob", "carol"] {
+            let info = mock_info(bidder, &[]);
+            let err = try_withdraw_bid(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+            match err {
+                ContractError::NoBid {} => {}
+                e => panic!("unexpected: {}", e),
+            }
+        }
+
+        // a bid on an option that is executed can still be withdrawn
+        let info = mock_info("dave", &coins(5, "ATOM"));
+        let _ = try_place_bid(deps.as_mut(), mock_env(), info, 1, Expiration::Never {}).unwrap();
+        let info = mock_info("creator", &coins(40, "ETH"));
+        let _ = try_execute_lenient(deps.as_mut(), mock_env(), info, 1).unwrap();
+        let info = mock_info("dave", &[]);
+        let res = try_withdraw_bid(deps.as_mut(), mock_env(), info, 1).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "dave".into(),
+                amount: coins(5, "ATOM"),
+            })]
+        );
+    }
+
+    #[test]
+    fn settle_hook() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let hook = SettleHook {
+            contract: Addr::unchecked("vault"),
+            msg_prefix: Some(Binary::from(b"pool-7".to_vec())),
+        };
+
+        // the hook must be a valid address
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            on_settle: Some(SettleHook {
+                contract: Addr::unchecked("x"),
+                msg_prefix: None,
+            }),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            on_settle: Some(hook.clone()),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            on_settle: Some(hook),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        let hook_msg = |option_id, outcome, counter_offer| SubMsg {
+            id: option_id,
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault".into(),
+                msg: to_binary(&SettleHookMsg::OptionSettled(OptionSettledMsg {
+                    option_id,
+                    outcome,
+                    counter_offer,
+                    collateral: coins(1, "BTC"),
+                    msg_prefix: Some(Binary::from(b"pool-7".to_vec())),
+                }))
+                .unwrap(),
+                send: vec![],
+            }),
+            gas_limit: None,
+            reply_on: ReplyOn::Error,
+        };
+
+        // execute notifies the hook alongside the payouts
+        let info = mock_info("creator", &counter_offer);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.submessages,
+            vec![hook_msg(0, SettleOutcome::Executed, counter_offer)]
+        );
+        assert_eq!(res.messages.len(), 2);
+
+        // burn notifies the hook without a counter_offer
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, 1).unwrap();
+        assert_eq!(
+            res.submessages,
+            vec![hook_msg(1, SettleOutcome::Burned, vec![])]
+        );
+        assert_eq!(res.messages.len(), 1);
+
+        // a failing hook only leaves a trace
+        let failure = Reply {
+            id: 1,
+            result: ContractResult::Err("vault is broke".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), failure).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "settle_hook_failed"),
+                attr("option_id", "1"),
+                attr("error", "vault is broke"),
+            ]
+        );
+
+        // nor does anything but a failed hook reply
+        for unexpected in [
+            Reply {
+                id: 1,
+                result: ContractResult::Ok(SubcallResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+            Reply {
+                id: 2,
+                result: ContractResult::Err("unknown".to_string()),
+            },
+        ] {
+            let _ = reply(deps.as_mut(), mock_env(), unexpected).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn last_execution() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // nothing executed yet
+        assert_eq!(None, query_last_execution(deps.as_ref()).unwrap());
+
+        let mut env = mock_env();
+        env.block.height = 54_321;
+        let info = mock_info("creator", &counter_offer);
+        let _ = try_execute(deps.as_mut(), env, info, ExecuteOptionMsg::default()).unwrap();
+
+        // the record outlives the option
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::LastExecution {}).unwrap();
+        let record: LastExecutionResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            Some(ExecutionRecord {
+                id: SINGLETON_ID,
+                executor: Addr::unchecked("creator"),
+                height: 54_321,
+                counter_offer,
+                collateral,
+            }),
+            record
+        );
+    }
+
+    #[test]
+    fn canonical_messages() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: vec![coin(40, "ETH"), coin(10, "ATOM")],
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(1, "BTC"), coin(5, "AKT")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // counter_offer to creator first, then collateral to owner,
+        // coins sorted by denom within each send
+        let info = mock_info("owner", &[coin(40, "ETH"), coin(10, "ATOM")]);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: vec![coin(10, "ATOM"), coin(40, "ETH")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "owner".into(),
+                    amount: vec![coin(5, "AKT"), coin(1, "BTC")],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn burn() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // cannot burn before expiry
+        let mut env = mock_env();
+        env.block.height = 99_999;
+        let info = mock_info("creator", &[]);
+        let err = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotExpired {
+                expires,
+                current_height,
+            } => {
+                assert_eq!(100_000, expires);
+                assert_eq!(99_999, current_height);
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // cannot send funds with burn
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &coins(3, "ATOM"));
+        let err = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::FundsNotAllowed { received } => assert_eq!(coins(3, "ATOM"), received),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // burn at expiry refunds the creator
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "burn"),
+                attr("option_id", "0"),
+                attr("burner", "creator"),
+                attr("creator", "creator"),
+                attr("refunded", "1BTC"),
+            ]
+        );
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral,
+            })]
+        );
+
+        // check deleted
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+    }
+
+    #[test]
+    fn reclaim() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = coins(1, "BTC");
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let msg = InstantiateMsg {
+            counter_offer: create.counter_offer.clone(),
+            expires: create.expires,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &collateral);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // only the creator can reclaim
+        let info = mock_info("anyone", &[]);
+        let err = try_reclaim(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // no funds accepted
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = try_reclaim(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::FundsNotAllowed { received } => assert_eq!(coins(1, "BTC"), received),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // reclaim before expiry while never transferred
+        let info = mock_info("creator", &[]);
+        let res = try_reclaim(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral,
+            })]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "reclaim"), attr("option_id", "0")]
+        );
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+
+        // once transferred, reclaim is impossible while someone else owns it
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let err = try_reclaim(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // and stays impossible after it is transferred back to the creator
+        let info = mock_info("owner", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            1,
+            "creator".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        let err = try_reclaim(deps.as_mut(), mock_env(), info, 1).unwrap_err();
+        match err {
+            ContractError::Transferred { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn relinquish() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // only the owner can relinquish
+        let info = mock_info("creator", &[]);
+        let err = try_relinquish(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the option returns to the creator, funds stay put
+        let info = mock_info("owner", &[]);
+        let res = try_relinquish(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "relinquish"), attr("option_id", "0")]
+        );
+        let state = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!(state.creator, state.owner);
+        assert_eq!(coins(1, "BTC"), state.collateral);
+    }
+
+    #[test]
+    fn extension() {
+        let mut deps = mock_dependencies(&[]);
+
+        let incentive = coins(5, "ETH");
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            max_duration_blocks: Some(200_000),
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // creation respects the max duration too
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 300_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = try_create(deps.as_mut(), mock_env(), info, create).unwrap_err();
+        match err {
+            ContractError::DurationTooLong { max_expires } => assert_eq!(212_345, max_expires),
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // only the creator proposes, strictly later and within the max duration
+        let info = mock_info("owner", &incentive);
+        let err = try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 150_000)
+            .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &incentive);
+        let err = try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 100_000)
+            .unwrap_err();
+        match err {
+            ContractError::Std(StdError::GenericErr { .. }) => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &incentive);
+        let err = try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 212_346)
+            .unwrap_err();
+        match err {
+            ContractError::DurationTooLong { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("creator", &incentive);
+        let _ =
+            try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 150_000).unwrap();
+        let info = mock_info("creator", &incentive);
+        let err = try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 160_000)
+            .unwrap_err();
+        match err {
+            ContractError::ExtensionPending { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        assert_eq!(
+            Some(Extension {
+                proposer: Addr::unchecked("creator"),
+                new_expires: 150_000,
+                incentive: incentive.clone(),
+            }),
+            query_extension(deps.as_ref(), SINGLETON_ID).unwrap()
+        );
+
+        // only the owner accepts, collecting the incentive
+        let info = mock_info("creator", &[]);
+        let err = try_accept_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("owner", &[]);
+        let res = try_accept_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".into(),
+                amount: incentive.clone(),
+            })]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "accept_extension"),
+                attr("option_id", "0"),
+                attr("expires_height", "150000"),
+            ]
+        );
+        assert_eq!(
+            150_000,
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().expires
+        );
+        assert_eq!(None, query_extension(deps.as_ref(), SINGLETON_ID).unwrap());
+
+        // rejection refunds the proposer
+        let info = mock_info("owner", &[]);
+        let err = try_reject_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NoExtension { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("creator", &incentive);
+        let _ =
+            try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 160_000).unwrap();
+        let info = mock_info("owner", &[]);
+        let res = try_reject_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: incentive.clone(),
+            })]
+        );
+        assert_eq!(
+            150_000,
+            query_config(deps.as_ref(), SINGLETON_ID).unwrap().expires
+        );
+
+        // a proposal dies on transfer
+        let info = mock_info("creator", &incentive);
+        let _ =
+            try_propose_extension(deps.as_mut(), mock_env(), info, SINGLETON_ID, 160_000).unwrap();
+        let info = mock_info("owner", &[]);
+        let res = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "buyer".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: incentive,
+            })]
+        );
+        assert_eq!(None, query_extension(deps.as_ref(), SINGLETON_ID).unwrap());
+    }
+
+    #[test]
+    fn renounce() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the creator cannot renounce an option it still holds
+        let info = mock_info("creator", &[]);
+        let err = try_renounce(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotTransferred { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // only the owner can renounce
+        let info = mock_info("creator", &[]);
+        let err = try_renounce(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // no funds accepted
+        let info = mock_info("owner", &coins(40, "ETH"));
+        let err = try_renounce(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::FundsNotAllowed { received } => assert_eq!(coins(40, "ETH"), received),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // not after expiry
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("owner", &[]);
+        let err = try_renounce(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Expired { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // owner hands the collateral back to the creator
+        let info = mock_info("owner", &[]);
+        let res = try_renounce(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral,
+            })]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "renounce"), attr("option_id", "0")]
+        );
+
+        // check deleted
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+    }
+
+    #[test]
+    fn burn_authorization() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.height = 200_000;
+
+        // a stranger cannot burn
+        let info = mock_info("anyone", &[]);
+        let err = try_burn(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the owner can burn, refunding the creator
+        let info = mock_info("owner", &[]);
+        let res = try_burn(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral.clone(),
+            })]
+        );
+
+        // the creator can burn
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_burn(deps.as_mut(), env.clone(), info, SINGLETON_ID).unwrap();
+
+        // anyone can burn once the contract opens burning to keepers
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            permissionless_burn: true,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("anyone", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn poke() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            grace_blocks: 100,
+            keeper_bounty_bps: 250,
+            auto_burn: true,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1_000, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // nobody can poke before expiry
+        let mut env = mock_env();
+        env.block.height = 99_999;
+        let info = mock_info("anyone", &[]);
+        let err = try_poke(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::NotExpired {
+                expires,
+                current_height,
+            } => assert_eq!((100_000, 99_999), (expires, current_height)),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // once expired anyone can, without a grace period, refunding the creator in full
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("anyone", &[]);
+        let res = try_poke(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "burn"),
+                attr("option_id", "0"),
+                attr("burner", "anyone"),
+                attr("creator", "creator"),
+                attr("refunded", "1000BTC"),
+            ]
+        );
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1_000, "BTC"),
+            })]
+        );
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+
+        // options created without auto_burn cannot be poked
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1_000, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("anyone", &[]);
+        let err = try_poke(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected: {}", e),
+        }
+    }
+
+    #[test]
+    fn keeper_burn() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = vec![coin(1_000, "BTC"), coin(30, "ATOM")];
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            permissionless_burn: true,
+            grace_blocks: 100,
+            keeper_bounty_bps: 250,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // inside the grace period only the creator or owner may burn
+        let mut env = mock_env();
+        env.block.height = 100_099;
+        let info = mock_info("keeper", &[]);
+        let err = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::GracePeriod { until } => assert_eq!(100_100, until),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // after the grace period a stranger burns and collects the bounty,
+        // the 0.75 ATOM share rounds down to zero and is skipped
+        let mut env = mock_env();
+        env.block.height = 100_100;
+        let info = mock_info("keeper", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "burn"),
+                attr("option_id", "0"),
+                attr("burner", "keeper"),
+                attr("creator", "creator"),
+                attr("refunded", "30ATOM,975BTC"),
+                attr("bounty", "25BTC"),
+            ]
+        );
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: vec![coin(30, "ATOM"), coin(975, "BTC")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "keeper".into(),
+                    amount: coins(25, "BTC"),
+                }),
+            ]
+        );
+
+        // the creator burning inside the grace period pays no bounty
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            permissionless_burn: true,
+            grace_blocks: 100,
+            keeper_bounty_bps: 250,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: vec![coin(30, "ATOM"), coin(1_000, "BTC")],
+            })]
+        );
+
+        // bounty too large is rejected
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            keeper_bounty_bps: MAX_KEEPER_BOUNTY_BPS + 1,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    }
+
+    #[test]
+    fn burn_many() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height = 100_000;
+
+        // empty, duplicated and oversized batches are rejected
+        let too_many: Vec<u64> = (0..=MAX_BURN_BATCH as u64).collect();
+        for ids in [vec![], vec![SINGLETON_ID, SINGLETON_ID], too_many] {
+            let info = mock_info("creator", &[]);
+            let err = try_burn_many(deps.as_mut(), env.clone(), info, ids).unwrap_err();
+            match err {
+                ContractError::InvalidBatch { max } => assert_eq!(MAX_BURN_BATCH, max),
+                e => panic!("unexpected: {}", e),
+            }
+        }
+
+        // an unknown id fails the whole batch
+        let info = mock_info("creator", &[]);
+        let err =
+            try_burn_many(deps.as_mut(), env.clone(), info, vec![SINGLETON_ID, 7]).unwrap_err();
+        match err {
+            ContractError::OptionNotFound { id } => assert_eq!(7, id),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // an unexpired id fails the whole batch
+        let info = mock_info("creator", &[]);
+        let err = try_burn_many(deps.as_mut(), mock_env(), info, vec![SINGLETON_ID]).unwrap_err();
+        match err {
+            ContractError::NotExpired { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+
+        // expired batch refunds the creator
+        let info = mock_info("creator", &[]);
+        let res = try_burn_many(deps.as_mut(), env, info, vec![SINGLETON_ID]).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+        let _ = query_config(deps.as_ref(), SINGLETON_ID).unwrap_err();
+    }
+
+    #[test]
+    fn burn_many_merges_refunds() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let create = CreateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(2, "BTC"), coin(7, "ATOM")]);
+        let _ = try_create(deps.as_mut(), mock_env(), info, create.clone()).unwrap();
+        let info = mock_info("creator", &coins(5, "BTC"));
+        let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        // all expired, one send carrying the totals
+        let mut env = mock_env();
+        env.block.height = 100_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn_many(deps.as_mut(), env, info, vec![0, 1, 2]).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: vec![coin(7, "ATOM"), coin(8, "BTC")],
+            })]
+        );
+        let res = query_list(deps.as_ref(), None, None).unwrap();
+        assert!(res.options.is_empty());
+    }
+
+    #[test]
+    fn settle_expired() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_300,
+            grace_blocks: 10,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for (writer, expires) in [("alice", 100_100), ("bob", 100_200), ("alice", 100_050)] {
+            let create = CreateMsg {
+                counter_offer: coins(40, "ETH"),
+                expires,
+                ..Default::default()
+            };
+            let info = mock_info(writer, &coins(2, "BTC"));
+            let _ = try_create(deps.as_mut(), mock_env(), info, create).unwrap();
+        }
+
+        // nothing due yet
+        let mut env = mock_env();
+        env.block.height = 100_059;
+        let res = sudo(deps.as_mut(), env, SudoMsg::SettleExpired { limit: 10 }).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "settle_expired"), attr("settled", "0")]
+        );
+
+        // limit bounds the work, oldest expiry first
+        let mut env = mock_env();
+        env.block.height = 100_250;
+        let res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SettleExpired { limit: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1], attr("settled", "1"));
+        assert!(query_config(deps.as_ref(), 3).is_err());
+        assert!(query_config(deps.as_ref(), 1).is_ok());
+
+        // the rest of the due options are settled, refunds merged per creator
+        let res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SettleExpired { limit: 10 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1], attr("settled", "2"));
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".into(),
+                    amount: coins(2, "BTC"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".into(),
+                    amount: coins(2, "BTC"),
+                }),
+            ]
+        );
+
+        // calling again is a no-op, the live option stays
+        let res = sudo(deps.as_mut(), env, SudoMsg::SettleExpired { limit: 10 }).unwrap();
+        assert_eq!(res.attributes[1], attr("settled", "0"));
+        let res = query_list(deps.as_ref(), None, None).unwrap();
+        let ids: Vec<u64> = res.options.iter().map(|(id, _)| *id).collect();
+        assert_eq!(vec![SINGLETON_ID], ids);
+    }
+
+    #[test]
+    fn merge_payouts() {
+        let creator = Addr::unchecked("creator");
+        let keeper = Addr::unchecked("keeper");
+        let mut payouts = vec![];
+        add_payout(&mut payouts, &creator, vec![coin(10, "BTC")]).unwrap();
+        add_payout(&mut payouts, &keeper, vec![]).unwrap();
+        add_payout(
+            &mut payouts,
+            &creator,
+            vec![coin(5, "ATOM"), coin(3, "BTC")],
+        )
+        .unwrap();
+        add_payout(&mut payouts, &keeper, vec![coin(1, "BTC")]).unwrap();
+        assert_eq!(
+            payouts,
+            vec![
+                (creator, vec![coin(13, "BTC"), coin(5, "ATOM")]),
+                (keeper, vec![coin(1, "BTC")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn grace_period_boundaries() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            permissionless_burn: true,
+            grace_blocks: 50,
+            ..Default::default()
         };
-        let _env = mock_env();
         let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let res = query_settings(deps.as_ref()).unwrap();
+        assert_eq!(
+            res,
+            Settings {
+                permissionless_burn: true,
+                grace_blocks: 50,
+                keeper_bounty_bps: 0,
+                max_duration_blocks: None,
+                admin: None,
+                market_fee_bps: 0,
+                treasury: None,
+                max_collateral: None,
+            }
+        );
 
-        // random cannot transfer
-        let _env = mock_env();
-        let info = mock_info("anyone", &[]);
-        let err = try_transfer(deps.as_mut(), _env, info, Addr::unchecked("anyone")).unwrap_err();
-        match err {
-            ContractError::Unauthorized { .. } => {}
+        let burn_at = |deps: &mut OwnedDeps<_, _, _>, sender: &str, height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            try_burn(deps.as_mut(), env, mock_info(sender, &[]), SINGLETON_ID)
+        };
+
+        // one block before expiry nobody can burn
+        match burn_at(&mut deps, "creator", 99_999).unwrap_err() {
+            ContractError::NotExpired { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+        match burn_at(&mut deps, "keeper", 99_999).unwrap_err() {
+            ContractError::NotExpired { .. } => {}
             e => panic!("unexpected: {}", e),
         }
 
-        let _env = mock_env();
-        // owner can transfer
-        let info = mock_info("creator", &[]);
-        let res = try_transfer(deps.as_mut(), _env, info, Addr::unchecked("someone")).unwrap();
-        assert_eq!(res.attributes.len(), 2);
-        assert_eq!(
-            res.attributes[0],
-            Attribute {
-                key: "action".to_string(),
-                value: "transfer".to_string(),
+        // at expiry and on the last grace block keepers are still locked out
+        for height in [100_000, 100_049] {
+            match burn_at(&mut deps, "keeper", height).unwrap_err() {
+                ContractError::GracePeriod { until } => assert_eq!(100_050, until),
+                e => panic!("unexpected: {}", e),
             }
-        );
-        let res: State = query_config(deps.as_ref()).unwrap();
-        assert_eq!("someone", res.owner.as_str());
-        assert_eq!("creator", res.creator.as_str());
+        }
+
+        // first block after the grace period is open to keepers
+        let res = burn_at(&mut deps, "keeper", 100_050).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // the creator may burn on the very first expired block
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            permissionless_burn: true,
+            grace_blocks: 50,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _ = burn_at(&mut deps, "creator", 100_000).unwrap();
     }
 
     #[test]
-    fn execute() {
-        let mut deps = mock_dependencies(&coins(2, "token"));
-        // // we can just call .unwrap() to assert this was a success
+    fn bounty_math() {
+        // small amounts round down to nothing
+        let (refund, bounty) = split_bounty(coins(39, "BTC"), 250).unwrap();
+        assert_eq!(coins(39, "BTC"), refund);
+        assert!(bounty.is_empty());
+
+        // smallest amount that yields a bounty
+        let (refund, bounty) = split_bounty(coins(40, "BTC"), 250).unwrap();
+        assert_eq!(coins(39, "BTC"), refund);
+        assert_eq!(coins(1, "BTC"), bounty);
+
+        // zero bps pays nothing
+        let (refund, bounty) = split_bounty(coins(1_000_000, "BTC"), 0).unwrap();
+        assert_eq!(coins(1_000_000, "BTC"), refund);
+        assert!(bounty.is_empty());
+    }
+
+    #[test]
+    fn pause() {
+        let mut deps = mock_dependencies(&[]);
+
         let counter_offer = coins(40, "ETH");
         let collateral = coins(1, "BTC");
         let msg = InstantiateMsg {
             counter_offer: counter_offer.clone(),
             expires: 100_000,
+            ..Default::default()
         };
-        let _env = mock_env();
-        let info = mock_info("creator", &coins(1, "BTC"));
-
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), _env, info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // set new owner
-        let _env = mock_env();
         let info = mock_info("creator", &[]);
-        let _ = try_transfer(deps.as_mut(), _env, info, Addr::unchecked("owner")).unwrap();
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
 
-        // random cannot execute
-        let info = mock_info("anyone", &[]);
-        let err = try_execute(deps.as_mut(), mock_env(), info).unwrap_err();
+        // only creator can pause
+        let info = mock_info("owner", &[]);
+        let err = try_set_paused(deps.as_mut(), mock_env(), info, SINGLETON_ID, true).unwrap_err();
         match err {
             ContractError::Unauthorized { .. } => {}
             e => panic!("unexpected: {}", e),
         }
 
-        // expired cannot execute
-        let mut _env = mock_env();
-        _env.block.height = 200_000;
-        let info = mock_info("owner", &counter_offer);
-        let err = try_execute(deps.as_mut(), _env, info).unwrap_err();
+        let info = mock_info("creator", &[]);
+        let res = try_set_paused(deps.as_mut(), mock_env(), info, SINGLETON_ID, true).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_paused"),
+                attr("option_id", "0"),
+                attr("paused", "true")
+            ]
+        );
+        assert!(query_config(deps.as_ref(), SINGLETON_ID).unwrap().paused);
 
+        // paused blocks transfer
+        let info = mock_info("owner", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "someone".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
         match err {
-            ContractError::Std(from) => match from {
-                StdError::GenericErr { msg, .. } => assert_eq!("option expired", msg.as_str()),
-                e => panic!("unexpected: {}", e),
-            },
+            ContractError::Paused { .. } => {}
             e => panic!("unexpected: {}", e),
         }
 
-        // bad counter_offer cannot execute
-        let info = mock_info("owner", &coins(39, "ETH"));
-        let err = try_execute(deps.as_mut(), mock_env(), info).unwrap_err();
+        // paused blocks execute
+        let info = mock_info("owner", &counter_offer);
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::Paused { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // unpause and execute works again
+        let info = mock_info("creator", &[]);
+        let _ = try_set_paused(deps.as_mut(), mock_env(), info, SINGLETON_ID, false).unwrap();
+        let info = mock_info("owner", &counter_offer);
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(res.messages.len(), 2);
+    }
+
+    #[test]
+    fn burn_while_paused() {
+        let mut deps = mock_dependencies(&[]);
+
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let _ = try_set_paused(deps.as_mut(), mock_env(), info, SINGLETON_ID, true).unwrap();
+
+        // burn still refunds the creator while paused
+        let mut env = mock_env();
+        env.block.height = 200_000;
+        let info = mock_info("creator", &[]);
+        let res = try_burn(deps.as_mut(), env, info, SINGLETON_ID).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: collateral,
+            })]
+        );
+    }
 
+    #[test]
+    fn execute_expected_terms() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // set new owner
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "owner".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // mismatched owner aborts
+        let info = mock_info("owner", &counter_offer);
+        let err = try_execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteOptionMsg {
+                expected_owner: Some("creator".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
         match err {
-            ContractError::Std(from) => match from {
-                StdError::GenericErr { msg, .. } => {
-                    assert_eq!("must send exact counter_offer: [Coin { denom: \"ETH\", amount: Uint128(40) }]", msg.as_str())
-                }
-                e => panic!("unexpected: {}", e),
+            ContractError::StateChanged { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // mismatched counter_offer aborts
+        let info = mock_info("owner", &counter_offer);
+        let err = try_execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteOptionMsg {
+                expected_counter_offer: Some(coins(30, "ETH")),
+                ..Default::default()
             },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::TermsChanged {} => {}
             e => panic!("unexpected: {}", e),
         }
 
-        // proper execution
-        let mut _env = mock_env();
+        // matching terms proceed normally
         let info = mock_info("owner", &counter_offer);
-        let res = try_execute(deps.as_mut(), _env, info).unwrap();
+        let res = try_execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteOptionMsg {
+                expected_owner: Some("owner".to_string()),
+                expected_counter_offer: Some(vec![coin(15, "ETH"), coin(25, "ETH")]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
         assert_eq!(res.messages.len(), 2);
         assert_eq!(
             res.messages[0],
@@ -296,15 +12276,381 @@ mod tests {
                 amount: counter_offer,
             })
         );
+    }
+
+    /// Hash the group members of option `id` approve to take `action`
+    fn action_hash(deps: Deps, id: u64, action: GroupAction) -> Binary {
+        let msg = QueryMsg::GroupActionHash {
+            id: Some(id),
+            action,
+        };
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn shareholders() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &[coin(100, "BTC"), coin(7, "ATOM")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // shares must be positive
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_to_shareholders(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec![("alice".into(), 70), ("bob".into(), 0)],
+            ShareQuorum::Majority,
+        )
+        .unwrap_err();
+
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_to_shareholders(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec![("alice".into(), 70), ("bob".into(), 30)],
+            ShareQuorum::Majority,
+        )
+        .unwrap();
+        let state = load_option(&deps.storage, SINGLETON_ID).unwrap();
+        assert_eq!(state.owner, mock_env().contract.address);
+
+        // bob alone holds no majority
+        let info = mock_info("bob", &coins(40, "ETH"));
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::SharesNotMet { approved, required } => {
+                assert_eq!((approved, required), (30, 51))
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // alice does, and the collateral is split 70/30 with leftovers going to her
+        let info = mock_info("alice", &coins(40, "ETH"));
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(40, "ETH"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".into(),
+                    amount: vec![coin(5, "ATOM"), coin(70, "BTC")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".into(),
+                    amount: vec![coin(2, "ATOM"), coin(30, "BTC")],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn shareholders_unanimous() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(100, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_to_shareholders(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec![("alice".into(), 70), ("bob".into(), 30)],
+            ShareQuorum::All,
+        )
+        .unwrap();
+
+        // even the larger holder needs everyone
+        let info = mock_info("alice", &coins(40, "ETH"));
+        let err =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap_err();
+        match err {
+            ContractError::SharesNotMet { approved, required } => {
+                assert_eq!((approved, required), (70, 100))
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // outsiders cannot approve
+        let hash = action_hash(
+            deps.as_ref(),
+            SINGLETON_ID,
+            GroupAction::Execute { recipient: None },
+        );
+        let info = mock_info("carol", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash.clone())
+            .unwrap_err();
+
+        let info = mock_info("bob", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash).unwrap();
+        let info = mock_info("alice", &coins(40, "ETH"));
+        let res =
+            try_execute(deps.as_mut(), mock_env(), info, ExecuteOptionMsg::default()).unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".into(),
+                amount: coins(30, "BTC"),
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_to_group() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            counter_offer: coins(40, "ETH"),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let members = vec!["alice".to_string(), "bob".to_string(), "carol".to_string()];
+
+        // threshold must be reachable
+        for threshold in [0, 4] {
+            let info = mock_info("creator", &[]);
+            let _ = try_transfer_to_group(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                SINGLETON_ID,
+                members.clone(),
+                threshold,
+            )
+            .unwrap_err();
+        }
+
+        // only the owner can hand the option to a group
+        let info = mock_info("anyone", &[]);
+        let err = try_transfer_to_group(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            members.clone(),
+            2,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let res = try_transfer_to_group(deps.as_mut(), mock_env(), info, SINGLETON_ID, members, 2)
+            .unwrap();
+        assert_eq!(res.attributes[0], attr("action", "transfer_to_group"));
+
+        // the contract holds the option for the group
+        let res = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("cosmos2contract", res.owner.as_str());
+        let res = query_group(deps.as_ref(), SINGLETON_ID).unwrap().unwrap();
+        assert_eq!(3, res.members.len());
+        assert_eq!(2, res.threshold);
+
+        // non-members cannot act for the group
+        let info = mock_info("dave", &[]);
+        let err = try_approve_action(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            Binary::default(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized { .. } => {}
+            e => panic!("unexpected: {}", e),
+        }
+
+        // a single member does not meet the threshold
+        let info = mock_info("alice", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "dave".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ThresholdNotMet {
+                approvals,
+                threshold,
+            } => {
+                assert_eq!(1, approvals);
+                assert_eq!(2, threshold);
+            }
+            e => panic!("unexpected: {}", e),
+        }
+
+        // an approval only counts for the action it was given for
+        let hash = action_hash(
+            deps.as_ref(),
+            SINGLETON_ID,
+            GroupAction::Transfer {
+                recipient: "erin".to_string(),
+            },
+        );
+        let info = mock_info("bob", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash).unwrap();
+        let info = mock_info("alice", &[]);
+        let err = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "dave".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ThresholdNotMet { approvals, .. } => assert_eq!(1, approvals),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // the final member's call goes through once enough members approved
+        let hash = action_hash(
+            deps.as_ref(),
+            SINGLETON_ID,
+            GroupAction::Transfer {
+                recipient: "dave".to_string(),
+            },
+        );
+        let info = mock_info("carol", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash).unwrap();
+        let info = mock_info("alice", &[]);
+        let _ = try_transfer(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "dave".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let res = query_config(deps.as_ref(), SINGLETON_ID).unwrap();
+        assert_eq!("dave", res.owner.as_str());
+        assert_eq!(None, query_group(deps.as_ref(), SINGLETON_ID).unwrap());
+        assert_eq!(
+            None,
+            GROUP_APPROVALS
+                .may_load(
+                    &deps.storage,
+                    (SINGLETON_ID.into(), &Addr::unchecked("carol"))
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn group_stale_approvals() {
+        let mut deps = mock_dependencies(&[]);
+
+        let counter_offer = coins(40, "ETH");
+        let collateral = coins(1, "BTC");
+        let msg = InstantiateMsg {
+            counter_offer: counter_offer.clone(),
+            expires: 100_000,
+            ..Default::default()
+        };
+        let info = mock_info("creator", &collateral);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_to_group(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            vec!["alice".to_string(), "bob".to_string()],
+            2,
+        )
+        .unwrap();
+
+        // bob approves executing, then the option changes
+        let hash = action_hash(
+            deps.as_ref(),
+            SINGLETON_ID,
+            GroupAction::Execute {
+                recipient: Some("alice".into()),
+            },
+        );
+        let info = mock_info("bob", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash).unwrap();
+        let info = mock_info("creator", &[]);
+        let _ = try_transfer_creator(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            SINGLETON_ID,
+            "creator2".into(),
+        )
+        .unwrap();
+
+        // bob's approval no longer counts
+        let info = mock_info("alice", &counter_offer);
+        let err = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap_err();
+        match err {
+            ContractError::ThresholdNotMet { approvals, .. } => assert_eq!(1, approvals),
+            e => panic!("unexpected: {}", e),
+        }
+
+        // approving the option as it now stands lets alice execute it, to herself only
+        let hash = action_hash(
+            deps.as_ref(),
+            SINGLETON_ID,
+            GroupAction::Execute {
+                recipient: Some("alice".into()),
+            },
+        );
+        let info = mock_info("bob", &[]);
+        let _ = try_approve_action(deps.as_mut(), mock_env(), info, SINGLETON_ID, hash).unwrap();
+        let info = mock_info("alice", &counter_offer);
+        let msg = ExecuteOptionMsg {
+            collateral_recipient: Some(Addr::unchecked("mallory")),
+            ..Default::default()
+        };
+        let err = try_execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::ThresholdNotMet { approvals, .. } => assert_eq!(1, approvals),
+            e => panic!("unexpected: {}", e),
+        }
+        let info = mock_info("alice", &counter_offer);
+        let res = try_execute_lenient(deps.as_mut(), mock_env(), info, SINGLETON_ID).unwrap();
         assert_eq!(
             res.messages[1],
             CosmosMsg::Bank(BankMsg::Send {
-                to_address: "owner".into(),
+                to_address: "alice".into(),
                 amount: collateral,
             })
         );
-
-        // check deleted
-        let _ = query_config(deps.as_ref()).unwrap_err();
     }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,6 +8,167 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Option {id} not found")]
+    OptionNotFound { id: u64 },
+
+    #[error("Don't send funds with this message, received: {received:?}")]
+    FundsNotAllowed { received: Vec<Coin> },
+
+    #[error("Approval already expired")]
+    ExpiredApproval {},
+
+    #[error("Permit expired")]
+    ExpiredPermit {},
+
+    #[error("Invalid permit")]
+    InvalidPermit {},
+
+    #[error("Cannot transfer to the contract itself")]
+    CannotTransferToSelf {},
+
+    #[error("Option is paused")]
+    Paused {},
+
+    #[error("Option expired at height {expires}")]
+    Expired { expires: u64 },
+
+    #[error("Exercise window closed")]
+    ExerciseWindowClosed {},
+
+    #[error("Option not expired: expires at height {expires}, current height {current_height}")]
+    NotExpired { expires: u64, current_height: u64 },
+
+    #[error("Only creator or owner may burn until height {until}")]
+    GracePeriod { until: u64 },
+
+    #[error("Batch must hold between 1 and {max} distinct ids")]
+    InvalidBatch { max: usize },
+
+    #[error("Duplicate execution id")]
+    DuplicateExecution {},
+
+    #[error("Option state changed")]
+    StateChanged {},
+
+    #[error("Option terms changed")]
+    TermsChanged {},
+
+    #[error("Counter offer mismatch, missing: {missing:?}, extra: {extra:?}")]
+    CounterOfferMismatch {
+        /// Short of the counter_offer, per denom
+        missing: Vec<Coin>,
+        /// Sent beyond the counter_offer, per denom
+        extra: Vec<Coin>,
+    },
+
+    #[error("Memo must be between 1 and {max} bytes")]
+    InvalidMemo { max: usize },
+
+    #[error("Memo exceeds the maximum length")]
+    MemoTooLong {},
+
+    #[error("Option cannot be transferred until height {until}")]
+    TransferLocked { until: u64 },
+
+    #[error("Partial transfer must leave part of every collateral denom with the option")]
+    PartialTooLarge {},
+
+    #[error("Recipient is not allowed to hold the option")]
+    RecipientNotAllowed {},
+
+    #[error("Address {address} is blacklisted")]
+    Blacklisted { address: String },
+
+    #[error("Action approved by {approvals} of the {threshold} group members required")]
+    ThresholdNotMet { approvals: u32, threshold: u32 },
+
+    #[error("Action approved by {approved} shares of the {required} required")]
+    SharesNotMet { approved: u64, required: u64 },
+
+    #[error("Option is not transferable")]
+    NonTransferable {},
+
+    #[error("Options do not share creator and terms")]
+    IncompatibleOptions {},
+
+    #[error("Option holds no collateral")]
+    NoCollateral {},
+
+    #[error("Not supported for options with cw20 collateral")]
+    Cw20Collateral {},
+
+    #[error("Option is still held by its creator")]
+    NotTransferred {},
+
+    #[error("Collateral exceeds the cap for {denom}")]
+    CollateralTooLarge { denom: String },
+
+    #[error("Expiry must not exceed height {max_expires}")]
+    DurationTooLong { max_expires: u64 },
+
+    #[error("An extension is already pending")]
+    ExtensionPending {},
+
+    #[error("No extension pending")]
+    NoExtension {},
+
+    #[error("A transfer is already pending")]
+    TransferPending {},
+
+    #[error("No transfer pending")]
+    NoTransferPending {},
+
+    #[error("Option is not listed for sale")]
+    NotListed {},
+
+    #[error("No bid from this address")]
+    NoBid {},
+
+    #[error("Bid expired")]
+    ExpiredBid {},
+
+    #[error("Must send exactly the asking price")]
+    WrongPrice {},
+
+    #[error("Auction starts at height {start_height}")]
+    AuctionNotStarted { start_height: u64 },
+
+    #[error("Must send at least the current auction price")]
+    BelowAuctionPrice {},
+
+    #[error("Must send at least the premium")]
+    BelowPremium {},
+
+    #[error("Bundle {bundle_id} not found")]
+    BundleNotFound { bundle_id: u64 },
+
+    #[error("Bundle is no longer for sale, an option in it changed hands or was settled")]
+    BundleInvalid {},
+
+    #[error("Listing is private to another buyer")]
+    PrivateListing {},
+
+    #[error("Option is not up for auction")]
+    NoAuction {},
+
+    #[error("Auction has ended")]
+    AuctionEnded {},
+
+    #[error("Auction ends at height {end_height}")]
+    AuctionNotEnded { end_height: u64 },
+
+    #[error("Bid must be at least {minimum}")]
+    BidTooLow { minimum: Coin },
+
+    #[error("A bid already reached the buy-now price")]
+    BuyNowOutbid {},
+
+    #[error("Option was transferred and can only be burned after expiry")]
+    Transferred {},
+
+    #[error("Coins must be sorted by denom, with no duplicate denoms or zero amounts")]
+    NonCanonicalCoins {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,33 +1,833 @@
-use crate::state::State;
-use cosmwasm_std::{Addr, Coin};
+use crate::state::{
+    DutchAuction, EnglishAuction, ExecutionRecord, Extension, Group, PendingTransfer, Rounding,
+    Settings, SettleHook, ShareQuorum, State,
+};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Uint128};
+use cw0::Expiration;
+use cw2::ContractVersion;
+use cw20::{Balance, Cw20CoinVerified, Cw20ReceiveMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     // owner and creator come from env
     // collateral comes from env
     pub counter_offer: Vec<Coin>,
     pub expires: u64,
+    /// Last height the option can be executed at, must not be after expires
+    pub exercise_deadline: Option<u64>,
+    /// Rounding applied to fractional payouts, defaults to Floor
+    pub rounding: Option<Rounding>,
+    /// Contract notified when the option is executed or burned
+    pub on_settle: Option<SettleHook>,
+    /// Set to false to keep the option with its first owner, defaults to true
+    pub transferable: Option<bool>,
+    /// Height before which the option cannot be transferred, at most expires
+    pub transfer_lock_until: Option<u64>,
+    /// Restricts who may hold the option besides the creator, at most MAX_ALLOWED_OWNERS
+    pub allowed_owners: Option<Vec<String>>,
+    /// Makes execution ignore collateral_recipient, always paying the executing owner
+    #[serde(default)]
+    pub lock_recipients: bool,
+    /// Creator's cut of every sale through Buy, AcceptBid or an auction, at most
+    /// MAX_ROYALTY_BPS. Plain transfers pay none
+    #[serde(default)]
+    pub royalty_bps: u64,
+    /// Price the creator offers the option at to its first buyer, see ExecuteMsg::BuyPrimary
+    pub premium: Option<Vec<Coin>>,
+    /// Owner of the new option if not the sender, e.g. a buyer it is written for directly.
+    /// The sender stays the creator
+    pub owner: Option<String>,
+    /// Lets anyone burn the option with Poke once expired, refunding the creator in full
+    #[serde(default)]
+    pub auto_burn: bool,
+    /// Rejects collateral above these amounts for every option written, denoms not listed are not capped
+    pub max_collateral: Option<Vec<Coin>>,
+    /// Lets anyone burn once expired, not just the creator or owner
+    #[serde(default)]
+    pub permissionless_burn: bool,
+    /// Blocks after expiry reserved for the creator and owner before keepers may burn
+    #[serde(default)]
+    pub grace_blocks: u64,
+    /// Keeper reward in basis points of the collateral, at most MAX_KEEPER_BOUNTY_BPS
+    #[serde(default)]
+    pub keeper_bounty_bps: u64,
+    /// Longest an option may run, in blocks from when it is created or extended
+    pub max_duration_blocks: Option<u64>,
+    /// Address allowed to maintain the blacklist
+    pub admin: Option<String>,
+    /// Accepts instantiating without funds, writing an option with no collateral
+    #[serde(default)]
+    pub allow_unfunded: bool,
+}
+
+/// Terms of an additional option, the sender becomes creator and owner
+/// and the sent funds its collateral
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CreateMsg {
+    pub counter_offer: Vec<Coin>,
+    pub expires: u64,
+    /// Last height the option can be executed at, must not be after expires
+    pub exercise_deadline: Option<u64>,
+    /// Rounding applied to fractional payouts, defaults to Floor
+    pub rounding: Option<Rounding>,
+    /// Contract notified when the option is executed or burned
+    pub on_settle: Option<SettleHook>,
+    /// Set to false to keep the option with its first owner, defaults to true
+    pub transferable: Option<bool>,
+    /// Height before which the option cannot be transferred, at most expires
+    pub transfer_lock_until: Option<u64>,
+    /// Restricts who may hold the option besides the creator, at most MAX_ALLOWED_OWNERS
+    pub allowed_owners: Option<Vec<String>>,
+    /// Makes execution ignore collateral_recipient, always paying the executing owner
+    #[serde(default)]
+    pub lock_recipients: bool,
+    /// Creator's cut of every sale through Buy, AcceptBid or an auction, at most
+    /// MAX_ROYALTY_BPS. Plain transfers pay none
+    #[serde(default)]
+    pub royalty_bps: u64,
+    /// Price the creator offers the option at to its first buyer, see ExecuteMsg::BuyPrimary
+    pub premium: Option<Vec<Coin>>,
+    /// Owner of the new option if not the sender, e.g. a buyer it is written for directly.
+    /// The sender stays the creator
+    pub owner: Option<String>,
+    /// Lets anyone burn the option with Poke once expired, refunding the creator in full
+    #[serde(default)]
+    pub auto_burn: bool,
 }
 
+// Every handler acts on the option created at instantiation (id 0) unless an id is given
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Owner can transfer to a new owner
-    Transfer { recipient: Addr },
+    /// Anyone can write another option held by this contract
+    Create(CreateMsg),
+    /// Owner can transfer to a new owner.
+    /// An execution_id can only be used once, so a double broadcast is rejected
+    Transfer {
+        id: Option<u64>,
+        recipient: String,
+        execution_id: Option<String>,
+        /// Free-text reference, e.g. a ticket number, of at most MAX_MEMO_LENGTH bytes
+        memo: Option<String>,
+    },
+    /// Owner can sell part of the option: the given share of the collateral, and the
+    /// matching share of the counter_offer, move into a new option owned by recipient.
+    /// The share must be the same for every collateral denom. The new option lives in this
+    /// contract under the next id, like any other, rather than in a contract of its own:
+    /// it keeps the parent's terms and collateral custody, and its id is known at once
+    TransferPartial {
+        id: Option<u64>,
+        recipient: String,
+        collateral: Vec<Coin>,
+    },
+    /// Anyone, typically a relayer, can submit a transfer the owner signed offline
+    TransferWithPermit {
+        id: Option<u64>,
+        recipient: String,
+        permit: Permit,
+    },
+    /// Like Transfer, but to a contract which is then called with ReceiveHookMsg.
+    /// The transfer is reverted if the call fails
+    SendOption {
+        id: Option<u64>,
+        contract: String,
+        msg: Binary,
+    },
+    /// Owner can hand the option to a group of members, who then need threshold
+    /// distinct approvals, the caller's included, to transfer, execute or burn it.
+    /// The contract holds the option on the group's behalf. Executing pays the calling
+    /// member unless it names a collateral_recipient, and approvals are for that recipient
+    TransferToGroup {
+        id: Option<u64>,
+        members: Vec<String>,
+        threshold: u32,
+    },
+    /// Owner can split the option into weighted shares. Holders approve actions like group
+    /// members, weighing approvals by share against the quorum, majority by default.
+    /// Execution pays the collateral out to every holder in proportion to its shares
+    TransferToShareholders {
+        id: Option<u64>,
+        shares: Vec<(String, u64)>,
+        quorum: Option<ShareQuorum>,
+    },
+    /// Group member or shareholder approves the action with the given hash,
+    /// see QueryMsg::GroupActionHash
+    ApproveAction {
+        id: Option<u64>,
+        action_hash: Binary,
+    },
+    /// Owner can let spender transfer the option on its behalf, never expiring by default
+    Approve {
+        id: Option<u64>,
+        spender: String,
+        expires: Option<Expiration>,
+    },
+    /// Owner can withdraw an approval
+    Revoke { id: Option<u64>, spender: String },
+    /// Owner can let addr execute the option too, e.g. one of a set of relayers.
+    /// The executor pays the counter_offer, the collateral still goes to the owner
+    AddExecutor { id: Option<u64>, addr: String },
+    /// Owner can withdraw an executor's right to execute
+    RemoveExecutor { id: Option<u64>, addr: String },
+    /// Owner can propose a transfer that only takes effect once the recipient accepts it.
+    /// One proposal per option, cleared by any change of owner
+    ProposeTransfer {
+        id: Option<u64>,
+        recipient: String,
+        /// Deadline for accepting, never by default
+        valid_until: Option<Expiration>,
+    },
+    /// Proposed recipient can accept the transfer and become the owner
+    AcceptTransfer { id: Option<u64> },
+    /// Owner can withdraw a proposed transfer before it is accepted
+    CancelTransfer { id: Option<u64> },
+    /// Anyone can delete up to limit expired transfer proposals
+    CleanupProposals { limit: Option<u32> },
     /// Owner can post counter_offer on unexpired option to execute and get the collateral
-    Execute {},
-    /// Burn will release collateral if expired
+    Execute(ExecuteOptionMsg),
+    /// Like Execute, but accepts at least the counter_offer per denom and refunds the surplus
+    ExecuteLenient { id: Option<u64> },
+    /// Burn will release collateral if expired. Only creator or owner can burn
+    /// unless the contract was instantiated with permissionless_burn, in which case
+    /// anyone can burn after the grace period and collect the keeper bounty
+    Burn { id: Option<u64> },
+    /// Burns several expired options at once, merging refunds per recipient.
+    /// Fails as a whole if any id cannot be burned
+    BurnMany { ids: Vec<u64> },
+    /// Anyone can burn an expired option created with auto_burn, without waiting out the
+    /// grace period. The creator gets all the collateral back, no keeper bounty is paid
+    Poke { id: Option<u64> },
+    /// Creator can take back the collateral of an option that was never transferred
+    Reclaim { id: Option<u64> },
+    /// Owner can hand the option back to the creator without moving any funds
+    Relinquish { id: Option<u64> },
+    /// Owner can hand an unexpired option back, returning the collateral to the creator
+    Renounce { id: Option<u64> },
+    /// Creator can propose a later expiry, the sent funds are paid to the owner on acceptance.
+    /// A pending proposal is refunded when the option changes hands or settles
+    ProposeExtension { id: Option<u64>, new_expires: u64 },
+    /// Owner can accept the pending extension and collect its incentive
+    AcceptExtension { id: Option<u64> },
+    /// Owner can reject, or the proposer withdraw, the pending extension, refunding the incentive
+    RejectExtension { id: Option<u64> },
+    /// Creator can add addresses to the option's owner allowlist, never remove them
+    AllowOwners {
+        id: Option<u64>,
+        addresses: Vec<String>,
+    },
+    /// Creator can hand the creator role (and its payouts) to a new address
+    TransferCreator { id: Option<u64>, recipient: String },
+    /// Admin can bar addresses from becoming owners or receiving payouts.
+    /// A blacklisted owner cannot execute, but the option can still be burned back to the creator
+    UpdateBlacklist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Admin can set the fee taken from every Buy, AcceptBid and auction settlement for
+    /// the treasury. The fee comes off the price first, the creator royalty off the rest
+    SetMarketFee {
+        market_fee_bps: u64,
+        /// Required unless market_fee_bps is zero
+        treasury: Option<String>,
+    },
+    /// Admin, or the creator of the first option if there is no admin, can sweep whatever the
+    /// contract holds beyond the collateral of live options, the incentives of pending
+    /// extensions and escrowed bids, e.g. rounding leftovers or funds sent to it directly
+    ClaimDust {},
+    /// Owner can offer the option for sale, replacing any earlier price.
+    /// Only private_to may buy it if given
+    List {
+        id: Option<u64>,
+        price: Vec<Coin>,
+        private_to: Option<String>,
+    },
+    /// Owner can offer the option for amount of the cw20 token, replacing any earlier price.
+    /// Buyers pay by sending the token with Cw20HookMsg::Buy. Only private_to may buy it if given
+    ListCw20 {
+        id: Option<u64>,
+        token: String,
+        amount: Uint128,
+        private_to: Option<String>,
+    },
+    /// Owner can offer the option in a Dutch auction, replacing any earlier price. The price
+    /// falls linearly with block height from start_price to end_price, where it then rests.
+    /// Only private_to may buy it if given
+    ListDutch {
+        id: Option<u64>,
+        start_price: Vec<Coin>,
+        end_price: Vec<Coin>,
+        start_height: u64,
+        end_height: u64,
+        private_to: Option<String>,
+    },
+    /// Owner can change the price of a listed option, not of one in a Dutch auction.
+    /// private_to replaces the listing's buyer, so None opens a private listing to anyone
+    UpdateListing {
+        id: Option<u64>,
+        price: Vec<Coin>,
+        private_to: Option<String>,
+    },
+    /// Owner can take the option off sale. Nothing is refunded, as no funds are held for asks
+    Delist { id: Option<u64> },
+    /// Anyone can buy a listed option by sending exactly its price, which goes to the owner.
+    /// The price is the one listed when the Buy executes, so a Buy racing a price update
+    /// fails with WrongPrice rather than settling at the old price.
+    /// In a Dutch auction, anything above the current price is refunded instead
+    Buy { id: Option<u64> },
+    /// Anyone but the creator can buy an option still offered at the premium it was created
+    /// with, paying the creator and refunding any surplus. The creator can withdraw the offer
+    /// with Delist, or with Reclaim along with the collateral
+    BuyPrimary { id: Option<u64> },
+    /// Owner of every option in ids can offer them together for a single price, e.g. the legs
+    /// of a strangle. Between 2 and MAX_BUNDLE_SIZE options, each in at most one bundle.
+    /// Selling, transferring, settling or delisting any of them invalidates the bundle
+    ListBundle { ids: Vec<u64>, price: Vec<Coin> },
+    /// Anyone can buy every option of a valid bundle by paying exactly its price. Each
+    /// option's creator royalty is taken on an equal share of the price
+    BuyBundle { bundle_id: u64 },
+    /// Owner can auction the option off to the highest bidder, the contract holding it until
+    /// the auction is settled. Bids are in the reserve's denom, each raising the high bid by at
+    /// least min_increment_bps, and may come in until end_height
+    StartAuction {
+        id: Option<u64>,
+        reserve: Coin,
+        min_increment_bps: u64,
+        end_height: u64,
+        /// Price in the reserve's denom at which a Buy wins the auction at once, refunding
+        /// the high bid. Off once a bid reaches it
+        buy_now: Option<Coin>,
+        /// Only bidder and buyer at the buy-now price if given
+        private_to: Option<String>,
+    },
+    /// Anyone but the seller can outbid the high bid with the sent funds, which are escrowed
+    /// while the bid stays highest and refunded once outbid. A bid close to the end extends
+    /// the auction by AUCTION_EXTENSION_BLOCKS, though never past the block before expiry
+    BidAuction { id: Option<u64> },
+    /// Anyone can settle an ended auction: the high bidder gets the option and the seller its
+    /// bid if the reserve was met, otherwise the option goes back to the seller. An option
+    /// expired by then also goes back, the high bid being refunded
+    SettleAuction { id: Option<u64> },
+    /// Anyone can offer to buy the option for the sent funds, which are escrowed until
+    /// withdrawn or accepted. Replaces and refunds the sender's previous bid.
+    /// Bids in a cw20 token are placed by sending it with Cw20HookMsg::PlaceBid
+    PlaceBid {
+        id: Option<u64>,
+        /// Deadline for accepting, never by default
+        valid_until: Option<Expiration>,
+    },
+    /// Bidder can take back its bid, also after the option was settled
+    WithdrawBid { id: Option<u64> },
+    /// Anyone can refund up to limit expired bids on the option, or any of its bids once the
+    /// option is settled, always to the bidders
+    RefundExpiredBids { id: Option<u64>, limit: Option<u32> },
+    /// Owner can sell the option to bidder for its unexpired bid, refunding every other bid
+    AcceptBid { id: Option<u64>, bidder: String },
+    /// Owner of both options can fold other_id into id. They must share creator, expiry and
+    /// all other terms but the amounts, which are added up. Any listing of id is dropped
+    Merge { id: Option<u64>, other_id: u64 },
+    /// Entry point of cw20 tokens sent to the contract, carrying a Cw20HookMsg
+    Receive(Cw20ReceiveMsg),
+    /// Owner can annotate the option, at most MAX_MEMO_LENGTH bytes, or clear the note with None
+    SetMemo {
+        id: Option<u64>,
+        memo: Option<String>,
+    },
+    /// Owner can ask keepers to remind it once at most blocks are left before expiry
+    SetReminder { id: Option<u64>, blocks: u64 },
+    /// Creator can halt execute and transfer in an emergency; burn stays available
+    SetPaused { id: Option<u64>, paused: bool },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ExecuteOptionMsg {
+    pub id: Option<u64>,
+    /// Execution aborts unless the option is still owned by this address,
+    /// guarding against a transfer racing the execution
+    pub expected_owner: Option<String>,
+    /// Execution aborts unless the option still asks for exactly this counter_offer,
+    /// compared per denom regardless of order
+    pub expected_counter_offer: Option<Vec<Coin>>,
+    /// Can only be used once, so a double broadcast is rejected
+    pub execution_id: Option<String>,
+    /// Validate the sent funds before expiry
+    #[serde(default)]
+    pub strict_order: bool,
+    /// Receives the collateral instead of the owner, ignored if the option locks recipients
+    pub collateral_recipient: Option<Addr>,
+}
+
+/// Owner's signed consent to a single transfer, see ExecuteMsg::TransferWithPermit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Permit {
+    /// Owner's compressed secp256k1 public key, the owner address is derived from it
+    pub pubkey: Binary,
+    /// Must match the owner's next nonce, see QueryMsg::PermitNonce
+    pub nonce: u64,
+    pub expires: Expiration,
+    /// Signature over the sha256 hash of the PermitMsg JSON
+    pub signature: Binary,
+}
+
+/// What the owner signs for a permit, serialized as JSON in this field order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitMsg {
+    /// Address of this contract
+    pub contract: String,
+    pub chain_id: String,
+    pub id: u64,
+    pub recipient: String,
+    pub nonce: u64,
+    pub expires: Expiration,
+}
+
+/// Option as exported for relaying, see QueryMsg::Export and contract::import_option.
+/// Fields serialize in declaration order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportedOption {
+    pub id: u64,
+    pub state: State,
+}
+
+/// Actions a group owning an option takes together
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAction {
+    Transfer {
+        recipient: String,
+    },
+    /// Execution paying the collateral to recipient, which a shared option ignores as it
+    /// pays every holder by its shares
+    Execute {
+        recipient: Option<String>,
+    },
     Burn {},
 }
 
+/// Privileged messages sent by the chain, e.g. from an end-blocker or governance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    /// Burns up to limit options past their grace period, refunding the creators
+    SettleExpired { limit: u64 },
+}
+
+/// Sent to the settle hook of an option's creator. A failing hook does not revert the settlement
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SettleHookMsg {
+    OptionSettled(OptionSettledMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OptionSettledMsg {
+    pub option_id: u64,
+    pub outcome: SettleOutcome,
+    /// Paid to the creator, empty unless executed
+    pub counter_offer: Vec<Coin>,
+    /// Released from the option, including any keeper bounty
+    pub collateral: Vec<Coin>,
+    /// Payload registered with the hook
+    pub msg_prefix: Option<Binary>,
+}
+
+/// Message embedded in a cw20 Send to this contract, paying with the sent tokens.
+/// Any contract may call Receive, so the token and the sender it reports are only as
+/// trustworthy as the calling contract: options backed by a token, and bids in one, are
+/// worth what that token is. Counterparties should check the token address, shown in
+/// the option's cw20_collateral and in the bid, before relying on them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Like ExecuteMsg::Create, the sent tokens becoming the collateral and the sender the
+    /// token reports the creator
+    CreateOption(CreateMsg),
+    /// Like ExecuteMsg::Buy, for an option listed with ListCw20
+    Buy { id: Option<u64> },
+    /// Like ExecuteMsg::PlaceBid
+    PlaceBid {
+        id: Option<u64>,
+        valid_until: Option<Expiration>,
+    },
+}
+
+/// Sent to the contract an option is sent to, see ExecuteMsg::SendOption
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveHookMsg {
+    ReceiveOption(ReceiveOptionMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiveOptionMsg {
+    /// Who sent the option, the previous owner or an approved spender
+    pub sender: String,
+    pub id: u64,
+    /// Payload given to SendOption
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SettleOutcome {
+    Executed,
+    Burned,
+    Reclaimed,
+    Renounced,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Config {},
+    Config {
+        id: Option<u64>,
+    },
+    /// Returns the fixed creator alongside the current owner
+    Parties {
+        id: Option<u64>,
+    },
+    /// Returns just the current owner and the option's status
+    OwnerOf {
+        id: Option<u64>,
+    },
+    /// Returns just the creator and the option's status
+    CreatorOf {
+        id: Option<u64>,
+    },
+    /// Returns what the owner pays and receives on execution
+    CostBasis {
+        id: Option<u64>,
+    },
+    /// Returns what executing the option at the current height would take and deliver
+    ExecutePreview {
+        id: Option<u64>,
+    },
+    /// Returns the option as a serialized ExportedOption, e.g. for an IBC packet mirroring it
+    /// on another chain
+    Export {
+        id: Option<u64>,
+    },
+    /// Returns how much of the option's life is left, a crude time-decay indicator
+    ThetaEstimate {
+        id: Option<u64>,
+    },
+    /// Returns whether the option is expired at the given height, past or future
+    ExpiredAt {
+        id: Option<u64>,
+        height: u64,
+    },
+    /// Returns whether the contract holds enough funds to release the option's collateral,
+    /// native or cw20, beyond what it owes for other options, extensions and bids
+    CanSettle {
+        id: Option<u64>,
+    },
+    /// Returns whether the option's stored addresses still pass address validation,
+    /// to detect state corrupted e.g. by a migration
+    Validate {
+        id: Option<u64>,
+    },
+    /// Returns the nonce the owner's next permit must carry
+    PermitNonce {
+        owner: String,
+    },
+    /// Returns whether address could execute the option at the current height
+    CanExecute {
+        id: Option<u64>,
+        address: String,
+    },
+    /// Sums the collateral the creator can burn back from expired options, by ascending id.
+    /// Totals cover the returned page only
+    CreatorClaimable {
+        creator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Lists the spenders approved to transfer the option, leaving out expired grants
+    Approvals {
+        id: Option<u64>,
+    },
+    /// Lists the options split off from this one by TransferPartial, by ascending id
+    Children {
+        id: Option<u64>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the group owning the option, if any
+    Group {
+        id: Option<u64>,
+    },
+    /// Returns the hash group members approve to let the group take action next
+    GroupActionHash {
+        id: Option<u64>,
+        action: GroupAction,
+    },
+    /// Returns the transfer proposal awaiting the recipient, if any and not expired
+    PendingTransfer {
+        id: Option<u64>,
+    },
+    /// Lists the bids escrowed on the option by ascending bidder, expired ones included
+    /// until refunded
+    Bids {
+        id: Option<u64>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists the bids escrowed by bidder by ascending option id, also on settled options
+    BidsByBidder {
+        bidder: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the running auction of the option, if any
+    Auction {
+        id: Option<u64>,
+    },
+    /// Returns the pending extension proposal, if any
+    Extension {
+        id: Option<u64>,
+    },
+    /// Returns the most recent execution, if any option was executed yet
+    LastExecution {},
+    /// Returns the contract-level burn settings, including the grace period
+    Settings {},
+    /// Returns the contract name and version stored at instantiation
+    Version {},
+    /// Returns the bounds inputs are checked against, to validate before sending
+    Limits {},
+    /// Sums up all live options. Reads every option, so with very many of them it may run
+    /// out of gas and need paginating like List
+    Stats {},
+    /// Lists options by ascending id
+    List {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Lists options for sale by ascending id, whether listed, auctioned or offered at their
+    /// premium, only those the seller sells if given
+    Listings {
+        seller: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
 // #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub type ConfigResponse = State;
+
+pub type SettingsResponse = Settings;
+
+pub type VersionResponse = ContractVersion;
+
+pub type LastExecutionResponse = Option<ExecutionRecord>;
+
+pub type ExtensionResponse = Option<Extension>;
+
+pub type PendingTransferResponse = Option<PendingTransfer>;
+
+pub type GroupResponse = Option<Group>;
+
+pub type AuctionResponse = Option<EnglishAuction>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitsResponse {
+    /// Longest transfer memo, in bytes
+    pub max_memo_length: u64,
+    /// Most ids a single BurnMany takes
+    pub max_burn_batch: u64,
+    /// Highest keeper bounty accepted at instantiation, in basis points
+    pub max_keeper_bounty_bps: u64,
+    /// Highest creator royalty accepted at creation, in basis points
+    pub max_royalty_bps: u64,
+    /// Highest market fee the admin can set, in basis points
+    pub max_market_fee_bps: u64,
+    /// Most entries a paginated query returns
+    pub max_page_limit: u32,
+    /// Entries a paginated query returns when no limit is given
+    pub default_page_limit: u32,
+    /// Longest an option may run, in blocks, if capped at instantiation
+    pub max_duration_blocks: Option<u64>,
+    /// Most addresses an owner allowlist can hold
+    pub max_allowed_owners: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PartiesResponse {
+    pub creator: Addr,
+    pub owner: Addr,
+    /// true once the option has left the creator's hands
+    pub is_transferred: bool,
+}
+
+/// Set as the data of a successful Transfer, for callers inspecting it in a reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferResponse {
+    pub id: u64,
+    pub previous_owner: Addr,
+    pub new_owner: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: Addr,
+    pub status: OptionStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorOfResponse {
+    pub creator: Addr,
+    pub status: OptionStatus,
+}
+
+/// Where an option stands at the current height
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionStatus {
+    Active,
+    /// Halted by the creator, see ExecuteMsg::SetPaused
+    Paused,
+    /// Past expiry, only left to be burned
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListResponse {
+    pub options: Vec<(u64, State)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidsResponse {
+    pub bids: Vec<BidInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidInfo {
+    pub id: u64,
+    pub bidder: Addr,
+    pub amount: Balance,
+    pub valid_until: Expiration,
+    /// Can no longer be accepted, only refunded
+    pub expired: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub id: u64,
+    pub seller: Addr,
+    pub price: ListingPrice,
+    pub expires: u64,
+    pub collateral: Vec<Coin>,
+    /// Only buyer of the listing, if private
+    pub private_to: Option<Addr>,
+}
+
+/// How an option for sale is priced, see ExecuteMsg::List, ListCw20, ListDutch, StartAuction
+/// and BuyPrimary
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingPrice {
+    Native(Vec<Coin>),
+    Cw20(Cw20CoinVerified),
+    Dutch(DutchAuction),
+    /// English auction, sold by its seller while the contract holds the option
+    Auction(EnglishAuction),
+    /// Creator's offer at the premium the option was created with
+    Primary(Vec<Coin>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutePreviewResponse {
+    /// What the owner must send
+    pub pays: Vec<Coin>,
+    /// What the owner receives
+    pub receives: Vec<Coin>,
+    /// false once expired or while paused
+    pub executable: bool,
+    /// Blocks left before expiry, 0 once expired
+    pub blocks_remaining: u64,
+    /// true once blocks_remaining is within the owner's reminder threshold
+    pub reminder_due: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChildrenResponse {
+    pub ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CanSettleResponse {
+    pub can_settle: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ThetaEstimateResponse {
+    /// Blocks until expiry, zero once expired
+    pub remaining: u64,
+    /// Blocks from creation to expiry
+    pub total: u64,
+    /// remaining / total
+    pub fraction: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub total_options: u64,
+    /// Per denom, sorted by denom
+    pub total_collateral: Vec<Coin>,
+    /// Per denom, sorted by denom
+    pub total_counter_offer: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpiredAtResponse {
+    pub expired: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidateResponse {
+    pub creator_valid: bool,
+    pub owner_valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitNonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CanExecuteResponse {
+    pub can: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorClaimableResponse {
+    /// Expired options still holding collateral
+    pub ids: Vec<u64>,
+    /// Their collateral, merged per denom
+    pub totals: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CostBasisResponse {
+    /// What the owner must pay
+    pub counter_offer: Vec<Coin>,
+    /// What the owner receives
+    pub collateral: Vec<Coin>,
+}
@@ -1,24 +1,353 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin, Storage};
-use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use cosmwasm_std::{Addr, Binary, Coin, Empty};
+use cw0::Expiration;
+use cw20::{Balance, Cw20CoinVerified};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
 
-pub static CONFIG_KEY: &[u8] = b"config";
+/// Id of the option created at instantiation, used by handlers when no id is given
+pub const SINGLETON_ID: u64 = 0;
+
+/// Maximum number of options burned in one BurnMany
+pub const MAX_BURN_BATCH: usize = 30;
+
+/// Longest memo accepted with a transfer, in bytes
+pub const MAX_MEMO_LENGTH: usize = 256;
+
+/// Most addresses an option's owner allowlist can hold
+pub const MAX_ALLOWED_OWNERS: usize = 50;
+
+/// Most members a group owning an option can have
+pub const MAX_GROUP_MEMBERS: usize = 20;
+
+/// Most options a bundle can hold
+pub const MAX_BUNDLE_SIZE: usize = 10;
+
+/// Upper bound on the keeper bounty, in basis points of the collateral
+pub const MAX_KEEPER_BOUNTY_BPS: u64 = 1_000;
+
+/// Upper bound on the creator royalty, in basis points of the sale price
+pub const MAX_ROYALTY_BPS: u64 = 1_000;
+
+/// Upper bound on the market fee, in basis points of the sale price
+pub const MAX_MARKET_FEE_BPS: u64 = 500;
+
+/// Blocks an auction is extended by when a bid comes in that close to its end
+pub const AUCTION_EXTENSION_BLOCKS: u64 = 10;
+
+pub const SETTINGS: Item<Settings> = Item::new("settings");
+
+/// Id handed out to the next created option
+pub const NEXT_ID: Item<u64> = Item::new("next_id");
+
+/// Id handed out to the next listed bundle, starting at zero
+pub const NEXT_BUNDLE_ID: Item<u64> = Item::new("next_bundle_id");
+
+pub struct OptionIndexes<'a> {
+    /// Options ordered by expiry height
+    pub expires: MultiIndex<'a, (U64Key, Vec<u8>), State>,
+    /// Options grouped by creator, ordered by id
+    pub creator: MultiIndex<'a, (Vec<u8>, Vec<u8>), State>,
+}
+
+impl<'a> IndexList<State> for OptionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<State>> + '_> {
+        let v: Vec<&dyn Index<State>> = vec![&self.expires, &self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Options keyed by id
+pub fn options<'a>() -> IndexedMap<'a, U64Key, State, OptionIndexes<'a>> {
+    let indexes = OptionIndexes {
+        expires: MultiIndex::new(
+            |s, pk| (U64Key::new(s.expires), pk),
+            "options",
+            "options__expires",
+        ),
+        creator: MultiIndex::new(
+            |s, pk| (s.creator.as_str().as_bytes().to_vec(), pk),
+            "options",
+            "options__creator",
+        ),
+    };
+    IndexedMap::new("options", indexes)
+}
+
+/// Pending extension proposals keyed by option id, at most one per option
+pub const EXTENSIONS: Map<U64Key, Extension> = Map::new("extensions");
+
+/// Transfers proposed by the owner and awaiting the recipient, keyed by option id
+pub const PENDING_TRANSFERS: Map<U64Key, PendingTransfer> = Map::new("pending_transfers");
+
+/// Spenders the owner allowed to transfer an option, until the given expiration.
+/// Cleared whenever the owner changes
+pub const APPROVALS: Map<(U64Key, &Addr), Expiration> = Map::new("approvals");
+
+/// Addresses the owner allowed to execute an option besides itself, keyed by
+/// (option id, executor). Cleared whenever the owner changes
+pub const EXECUTORS: Map<(U64Key, &Addr), Empty> = Map::new("executors");
+
+/// Groups owning an option in place of a single owner, keyed by option id
+pub const GROUPS: Map<U64Key, Group> = Map::new("groups");
+
+/// Action hash each group member last approved, keyed by (option id, member)
+pub const GROUP_APPROVALS: Map<(U64Key, &Addr), Binary> = Map::new("group_approvals");
+
+/// Funds escrowed by each bidder on an option, keyed by (option id, bidder).
+/// Kept after the option is settled, until withdrawn or refunded by anyone
+pub const BIDS: Map<(U64Key, &Addr), Bid> = Map::new("bids");
+
+/// Options listed together for a single price, keyed by bundle id. Left behind once
+/// invalidated, see State::bundle
+pub const BUNDLES: Map<U64Key, Bundle> = Map::new("bundles");
+
+/// English auctions of options the contract holds for their sellers, keyed by option id
+pub const AUCTIONS: Map<U64Key, EnglishAuction> = Map::new("auctions");
+
+/// Nonce the next permit signed by each owner must carry
+pub const PERMIT_NONCES: Map<&Addr, u64> = Map::new("permit_nonces");
+
+/// Addresses that may not become owners or receive payouts, maintained by the admin
+pub const BLACKLIST: Map<&Addr, Empty> = Map::new("blacklist");
+
+/// Options split off by TransferPartial, keyed by (parent id, child id)
+pub const CHILDREN: Map<(U64Key, U64Key), Empty> = Map::new("children");
+
+/// Outcome of the most recent execution, kept after the option itself is removed
+pub const LAST_EXECUTION: Item<ExecutionRecord> = Item::new("last_execution");
+
+/// Client-supplied execution ids already used, mapped to the height they were seen at
+pub const EXECUTION_IDS: Map<&str, u64> = Map::new("execution_ids");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub creator: Addr,
     pub owner: Addr,
     pub collateral: Vec<Coin>,
+    /// Collateral in a cw20 token instead, sent with Cw20HookMsg::CreateOption. Such an
+    /// option holds no native collateral and cannot be split, merged or shared
+    #[serde(default)]
+    pub cw20_collateral: Option<Cw20CoinVerified>,
     pub counter_offer: Vec<Coin>,
     pub expires: u64,
+    /// Height the option was created at, zero for options created before it was recorded
+    #[serde(default)]
+    pub created_at: u64,
+    /// Last height the option can be executed at, when earlier than expires
+    pub exercise_deadline: Option<u64>,
+    pub paused: bool,
+    pub rounding: Rounding,
+    /// Set once the option left the creator's hands, even if it came back later. The contract
+    /// holding it for an auction, a group or shareholders does not count
+    #[serde(default)]
+    pub transferred: bool,
+    /// Contract notified whenever the option is settled
+    pub on_settle: Option<SettleHook>,
+    /// Fixed at creation, when false the owner can only execute, burn or hand it back
+    #[serde(default = "default_transferable")]
+    pub transferable: bool,
+    /// Height before which the option cannot change hands
+    pub transfer_lock_until: Option<u64>,
+    /// Only these addresses, besides the creator, may hold the option. Unrestricted if None
+    pub allowed_owners: Option<Vec<Addr>>,
+    /// Reference given with the most recent Transfer, if any
+    pub last_transfer_memo: Option<String>,
+    /// Blocks before expiry from which the owner wants to be reminded, cleared on transfer
+    pub reminder_blocks: Option<u64>,
+    /// Fixed at creation, when true execution ignores any collateral_recipient override
+    #[serde(default)]
+    pub lock_recipients: bool,
+    /// Share of every market sale paid to the creator, in basis points. Fixed at creation
+    #[serde(default)]
+    pub royalty_bps: u64,
+    /// Fixed at creation, when true anyone can burn the option with Poke once expired
+    #[serde(default)]
+    pub auto_burn: bool,
+    /// Price the creator offered the option at on creation, cleared once it changes hands
+    #[serde(default)]
+    pub premium: Option<Vec<Coin>>,
+    /// Price the owner listed the option at, cleared whenever the owner changes
+    pub ask: Option<Vec<Coin>>,
+    /// Price in a cw20 token the owner listed the option at instead of ask, cleared whenever
+    /// the owner changes
+    #[serde(default)]
+    pub cw20_ask: Option<Cw20CoinVerified>,
+    /// Declining price the owner listed the option at, cleared whenever the owner changes
+    #[serde(default)]
+    pub auction: Option<DutchAuction>,
+    /// Bundle the owner listed the option in, cleared whenever the owner changes. The bundle
+    /// can only be bought while all its options still point to it
+    #[serde(default)]
+    pub bundle: Option<u64>,
+    /// Only buyer of the listing if set, cleared with the listing
+    #[serde(default)]
+    pub private_to: Option<Addr>,
+    /// Owner's own note on the option, cleared whenever the owner changes
+    pub memo: Option<String>,
+    /// Holders sharing the option held by the contract, with their weights.
+    /// Execution splits the collateral among them by weight
+    #[serde(default)]
+    pub shares: Option<Vec<(Addr, u64)>>,
+    /// Approving shares the holders need to act, see ExecuteMsg::TransferToShareholders
+    #[serde(default)]
+    pub share_quorum: ShareQuorum,
+}
+
+fn default_transferable() -> bool {
+    true
+}
+
+/// Members jointly owning an option, see ExecuteMsg::TransferToGroup
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Group {
+    pub members: Vec<Addr>,
+    /// Distinct members that must approve an action, the caller included
+    pub threshold: u32,
+}
+
+/// Callback registered by the creator, see SettleHookMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettleHook {
+    pub contract: Addr,
+    /// Opaque payload handed back in every callback, e.g. to identify the vault
+    pub msg_prefix: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutionRecord {
+    pub id: u64,
+    pub executor: Addr,
+    pub height: u64,
+    pub counter_offer: Vec<Coin>,
+    pub collateral: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransfer {
+    pub recipient: Addr,
+    /// Treated as if never proposed once expired
+    #[serde(default)]
+    pub valid_until: Expiration,
+}
+
+/// Funds escrowed by a bidder, see ExecuteMsg::PlaceBid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bid {
+    /// Native coins, or a cw20 token sent through ExecuteMsg::Receive
+    pub amount: Balance,
+    /// Cannot be accepted once expired, and anyone may then refund it to the bidder
+    #[serde(default)]
+    pub valid_until: Expiration,
+}
+
+/// Price falling linearly from start_price at start_height to end_price at end_height,
+/// see ExecuteMsg::ListDutch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuction {
+    pub start_price: Vec<Coin>,
+    /// Same denoms as start_price, none of them higher
+    pub end_price: Vec<Coin>,
+    pub start_height: u64,
+    pub end_height: u64,
+}
+
+/// Options sold together, see ExecuteMsg::ListBundle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bundle {
+    pub seller: Addr,
+    pub ids: Vec<u64>,
+    pub price: Vec<Coin>,
+}
+
+/// Ascending auction of an option, see ExecuteMsg::StartAuction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EnglishAuction {
+    pub seller: Addr,
+    /// Lowest bid the option is sold for, in the only denom bids are taken in
+    pub reserve: Coin,
+    /// Least each bid must raise the high bid by, in basis points of it
+    pub min_increment_bps: u64,
+    /// Pushed back by bids placed within AUCTION_EXTENSION_BLOCKS of it
+    pub end_height: u64,
+    /// Escrowed until outbid or the auction is settled
+    pub high_bid: Option<AuctionBid>,
+    /// Price in the reserve's denom a Buy ends the auction at, until a bid reaches it
+    #[serde(default)]
+    pub buy_now: Option<Coin>,
+    /// Only bidder and buyer at the buy-now price if set
+    #[serde(default)]
+    pub private_to: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionBid {
+    pub bidder: Addr,
+    pub amount: Coin,
+}
+
+/// Later expiry proposed by the creator, paying the owner an incentive to accept it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Extension {
+    /// Refunded the incentive if the extension is rejected or dropped
+    pub proposer: Addr,
+    pub new_expires: u64,
+    pub incentive: Vec<Coin>,
+}
+
+/// Direction in which fractional payouts are rounded
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    Floor,
+    Ceil,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::Floor
+    }
+}
+
+/// Shares whose holders must approve an action on a shared option
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareQuorum {
+    /// More than half of all shares
+    Majority,
+    /// Every holder
+    All,
 }
 
-pub fn config(storage: &mut dyn Storage) -> Singleton<State> {
-    singleton(storage, CONFIG_KEY)
+impl Default for ShareQuorum {
+    fn default() -> Self {
+        ShareQuorum::Majority
+    }
 }
 
-pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<State> {
-    singleton_read(storage, CONFIG_KEY)
+/// Contract-level settings, fixed at instantiation unless noted
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Settings {
+    /// Lets any address burn an expired option, not only its creator or owner
+    pub permissionless_burn: bool,
+    /// Blocks after expiry during which only the creator or owner may burn
+    pub grace_blocks: u64,
+    /// Share of the collateral, in basis points, paid to a keeper that burns the option
+    pub keeper_bounty_bps: u64,
+    /// Longest an option may run, in blocks from when it is created or extended
+    #[serde(default)]
+    pub max_duration_blocks: Option<u64>,
+    /// Maintains the blacklist, nobody does if None
+    #[serde(default)]
+    pub admin: Option<Addr>,
+    /// Share of every market sale paid to the treasury, in basis points. Set by the admin
+    #[serde(default)]
+    pub market_fee_bps: u64,
+    /// Receives the market fee, set by the admin along with it
+    #[serde(default)]
+    pub treasury: Option<Addr>,
+    /// Largest collateral any option may hold per denom, denoms not listed are not capped
+    #[serde(default)]
+    pub max_collateral: Option<Vec<Coin>>,
 }